@@ -0,0 +1,103 @@
+//! User-configurable groups of domains treated as equivalent for credential
+//! matching, e.g. so a credential saved for `google.com` still autofills on
+//! `youtube.com`. Layered on top of a small built-in default list - see
+//! [`build_equivalent_domain_groups`].
+
+use std::collections::HashMap;
+
+use super::extract_root_domain;
+
+/// Built-in groups of root domains operated by the same service, so a
+/// credential stored under one is still offered on another. Always present,
+/// regardless of what the caller passes as custom groups.
+static DEFAULT_EQUIVALENT_DOMAIN_GROUPS: &[&[&str]] = &[
+    &["google.com", "youtube.com"],
+    &["amazon.com", "amazon.co.uk", "amazon.de", "amazon.fr", "amazon.ca"],
+    &["microsoft.com", "live.com", "outlook.com"],
+];
+
+/// Builds a map from root domain to a canonical group id, combining
+/// [`DEFAULT_EQUIVALENT_DOMAIN_GROUPS`] with caller-supplied `custom_groups`
+/// (e.g. parsed from `CredentialMatcherInput::equivalent_domains`). Entries
+/// are expected to already be root domains; lookups in
+/// [`equivalent_domains_match`] resolve both sides to their root domain
+/// before consulting this map.
+pub fn build_equivalent_domain_groups(custom_groups: &[Vec<String>]) -> HashMap<String, usize> {
+    let mut groups = HashMap::new();
+    let mut next_id = 0usize;
+
+    for group in DEFAULT_EQUIVALENT_DOMAIN_GROUPS {
+        for domain in *group {
+            groups.insert((*domain).to_string(), next_id);
+        }
+        next_id += 1;
+    }
+    for group in custom_groups {
+        for domain in group {
+            groups.insert(domain.to_lowercase(), next_id);
+        }
+        next_id += 1;
+    }
+
+    groups
+}
+
+/// Returns true if `domain1` and `domain2` resolve to the same equivalent-
+/// domain group: their registrable roots are both present in `groups` and
+/// map to the same group id. Domains with no configured group never match
+/// this way, even if they happen to be equal (that's handled earlier, by the
+/// higher-precedence exact/root-domain checks).
+pub fn equivalent_domains_match(domain1: &str, domain2: &str, groups: &HashMap<String, usize>) -> bool {
+    if domain1.is_empty() || domain2.is_empty() {
+        return false;
+    }
+
+    let root1 = extract_root_domain(domain1);
+    let root2 = extract_root_domain(domain2);
+    if root1.is_empty() || root2.is_empty() {
+        return false;
+    }
+
+    match (groups.get(&root1), groups.get(&root2)) {
+        (Some(g1), Some(g2)) => g1 == g2,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_equivalent_domain_groups_merges_defaults_and_custom() {
+        let groups = build_equivalent_domain_groups(&[vec!["example.com".to_string(), "example.org".to_string()]]);
+
+        assert_eq!(groups.get("google.com"), groups.get("youtube.com"));
+        assert_eq!(groups.get("example.com"), groups.get("example.org"));
+        assert_ne!(groups.get("google.com"), groups.get("example.com"));
+    }
+
+    #[test]
+    fn test_equivalent_domains_match_defaults() {
+        let groups = build_equivalent_domain_groups(&[]);
+
+        assert!(equivalent_domains_match("accounts.google.com", "www.youtube.com", &groups));
+        assert!(!equivalent_domains_match("google.com", "example.com", &groups));
+    }
+
+    #[test]
+    fn test_equivalent_domains_match_custom_group() {
+        let groups = build_equivalent_domain_groups(&[vec!["mybrand.com".to_string(), "mybrand.io".to_string()]]);
+
+        assert!(equivalent_domains_match("mybrand.com", "app.mybrand.io", &groups));
+        assert!(!equivalent_domains_match("mybrand.com", "google.com", &groups));
+    }
+
+    #[test]
+    fn test_equivalent_domains_match_rejects_unconfigured_or_empty() {
+        let groups = build_equivalent_domain_groups(&[]);
+
+        assert!(!equivalent_domains_match("unconfigured-a.com", "unconfigured-b.com", &groups));
+        assert!(!equivalent_domains_match("", "youtube.com", &groups));
+    }
+}
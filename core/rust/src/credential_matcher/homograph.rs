@@ -0,0 +1,109 @@
+//! Lookalike/homograph domain detection, used as an anti-phishing signal.
+//!
+//! This does not attempt a full Unicode confusables mapping; it combines two
+//! cheap, high-signal heuristics instead:
+//! - Levenshtein edit distance between registrable root domains, to catch
+//!   near-miss typosquats (e.g. "paypaI.com" vs "paypal.com").
+//! - Mixed-script detection on the decoded Unicode host, to catch IDN
+//!   homographs (e.g. Cyrillic "а" standing in for Latin "a" in "apple.com").
+
+/// Compute the Levenshtein edit distance between two strings, using the
+/// standard two-row dynamic-programming recurrence.
+pub fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut prev_row: Vec<usize> = (0..=b.len()).collect();
+    let mut curr_row = vec![0usize; b.len() + 1];
+
+    for i in 1..=a.len() {
+        curr_row[0] = i;
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            curr_row[j] = (prev_row[j] + 1)
+                .min(curr_row[j - 1] + 1)
+                .min(prev_row[j - 1] + cost);
+        }
+        std::mem::swap(&mut prev_row, &mut curr_row);
+    }
+
+    prev_row[b.len()]
+}
+
+/// Returns true if `ascii_host` contains an IDNA punycode label (`xn--`),
+/// i.e. it was originally a non-ASCII (Unicode) hostname.
+fn is_punycode_host(ascii_host: &str) -> bool {
+    ascii_host.split('.').any(|label| label.starts_with("xn--"))
+}
+
+/// Returns true if `unicode_host` contains letters from more than one script
+/// family (e.g. Latin mixed with Cyrillic), which is the classic IDN
+/// homograph pattern: a handful of lookalike letters swapped into an
+/// otherwise-Latin brand name.
+fn has_mixed_scripts(unicode_host: &str) -> bool {
+    let mut saw_latin = false;
+    let mut saw_other_script = false;
+
+    for c in unicode_host.chars() {
+        if c.is_ascii_alphabetic() {
+            saw_latin = true;
+        } else if c.is_alphabetic() {
+            saw_other_script = true;
+        }
+    }
+
+    saw_latin && saw_other_script
+}
+
+/// Returns true if `ascii_host` (already IDNA-normalized, e.g. via
+/// `extract_domain`) looks like an IDN homograph: it's punycode-encoded and
+/// decodes back to a host mixing Latin letters with another script.
+pub fn is_confusable_host(ascii_host: &str) -> bool {
+    if !is_punycode_host(ascii_host) {
+        return false;
+    }
+
+    let (unicode_host, result) = idna::domain_to_unicode(ascii_host);
+    if result.is_err() {
+        // Couldn't cleanly decode back to Unicode - treat as suspicious
+        // rather than silently letting it through.
+        return true;
+    }
+
+    has_mixed_scripts(&unicode_host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levenshtein_distance() {
+        assert_eq!(levenshtein_distance("paypal.com", "paypal.com"), 0);
+        // 'l' -> 'i'
+        assert_eq!(levenshtein_distance("paypal.com", "paypai.com"), 1);
+        assert_eq!(levenshtein_distance("example.com", "exanple.com"), 1);
+        assert_eq!(levenshtein_distance("example.com", "different.com"), 8);
+    }
+
+    #[test]
+    fn test_is_confusable_host_plain_ascii() {
+        assert!(!is_confusable_host("apple.com"));
+    }
+
+    #[test]
+    fn test_is_confusable_host_single_script_not_confusable() {
+        // A domain written entirely in one non-Latin script is just a
+        // foreign-language domain, not a homograph of anything.
+        let ascii = idna::domain_to_ascii("\u{043f}\u{0440}\u{0438}\u{043c}\u{0435}\u{0440}.com").unwrap();
+        assert!(!is_confusable_host(&ascii));
+    }
+
+    #[test]
+    fn test_is_confusable_host_mixed_script() {
+        // "а" (U+0430, Cyrillic) + "pple.com" (Latin) punycode-encodes to an
+        // xn-- label that decodes back to a script-mixed host.
+        let ascii = idna::domain_to_ascii("\u{0430}pple.com").unwrap();
+        assert!(is_confusable_host(&ascii));
+    }
+}
@@ -4,21 +4,89 @@
 //! for cross-platform consistency with browser extensions, iOS, and Android.
 //!
 //! Algorithm Structure (Priority Order with Early Returns):
+//! 0. PRIORITY 0: Exact ID Lookup (query parses as a UUID - deep links, FFI lookups)
 //! 1. PRIORITY 1: App Package Name Exact Match (for mobile apps)
-//! 2. PRIORITY 2: URL Domain Matching (exact, subdomain, root domain)
+//! 2. PRIORITY 2: URL Domain Matching (exact, subdomain, root domain, equivalent domain group)
 //! 3. PRIORITY 3: Service Name Fallback (only for credentials without URLs - anti-phishing)
 //! 4. PRIORITY 4: Text/Page Title Matching (non-URL search)
 
 mod domain;
+mod equivalent_domains;
+mod homograph;
+mod public_suffix;
+mod query;
 mod stop_words;
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
-pub use domain::{extract_domain, extract_domain_with_port, extract_root_domain, DomainWithPort};
-use domain::{domains_match, is_app_package_name};
+pub use domain::{
+    extract_domain, extract_domain_with_port, extract_root_domain,
+    extract_root_domain_with_options, parse_domain, DomainError, DomainWithPort, ParsedDomain,
+};
+use domain::{domains_match, path_prefix_matches};
+use equivalent_domains::{build_equivalent_domain_groups, equivalent_domains_match};
+use homograph::{is_confusable_host, levenshtein_distance};
+pub use query::{parse_query, QueryKind};
 use stop_words::STOP_WORDS;
 
+/// Stored domains within this edit distance of the current site's root
+/// domain are flagged as a possible typosquat rather than auto-matched.
+const SUSPICIOUS_DOMAIN_EDIT_DISTANCE: usize = 2;
+
+// Base scores per match reason, highest precedence first. Gaps between tiers
+// leave room for `USERNAME_MATCH_BONUS` to break ties within a tier without
+// ever promoting a match into the tier above it.
+const SCORE_PATH_PREFIX: u32 = 100;
+const SCORE_EXACT_HOST_PORT: u32 = 90;
+const SCORE_EXACT_HOST: u32 = 80;
+const SCORE_REGISTRABLE_DOMAIN: u32 = 70;
+const SCORE_PACKAGE_NAME: u32 = 60;
+const SCORE_EQUIVALENT_GROUP: u32 = 50;
+const SCORE_TITLE_WORD: u32 = 40;
+const SCORE_URL_WORD: u32 = 35;
+const SCORE_TEXT_WORD: u32 = 30;
+
+/// Added to a URL-matching score when the credential's `username` equals
+/// `current_username`, to break ties among same-tier matches.
+const USERNAME_MATCH_BONUS: u32 = 5;
+
+/// Returns [`USERNAME_MATCH_BONUS`] if `cred`'s username matches
+/// `current_username`, 0 otherwise (including when either side is unknown).
+fn username_bonus(cred: &Credential, current_username: &Option<String>) -> u32 {
+    match current_username {
+        Some(username) if cred.username.as_deref() == Some(username.as_str()) => {
+            USERNAME_MATCH_BONUS
+        }
+        _ => 0,
+    }
+}
+
+/// Sorts `matches` by score (descending), deduplicates by id keeping the
+/// highest-scoring entry, truncates to `max_results`, and derives the
+/// legacy `matched_ids` list from what remains.
+fn finalize_matches(
+    mut matches: Vec<ScoredMatch>,
+    matched_priority: u8,
+    suspicious_ids: Vec<String>,
+    has_suspicious_matches: bool,
+    max_results: usize,
+) -> CredentialMatcherOutput {
+    matches.sort_by(|a, b| b.score.cmp(&a.score));
+    let mut seen_ids: HashSet<String> = HashSet::new();
+    matches.retain(|m| seen_ids.insert(m.id.clone()));
+    matches.truncate(max_results);
+    let matched_ids = matches.iter().map(|m| m.id.clone()).collect();
+
+    CredentialMatcherOutput {
+        matched_ids,
+        matched_priority,
+        matches,
+        suspicious_ids,
+        has_suspicious_matches,
+    }
+}
+
 /// Matching mode for credential filtering.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
@@ -27,6 +95,19 @@ pub enum AutofillMatchingMode {
     Default,
     UrlExact,
     UrlSubdomain,
+    /// Like `Default`, but credentials whose stored URL has a non-root path
+    /// (e.g. `example.com/admin`) only match when the current URL's path is
+    /// a segment-boundary prefix match of it, ranking above bare domain+port
+    /// matches. Credentials with no path on their stored URL are unaffected.
+    UrlPathPrefix,
+    /// Exact domain matches only: no subdomain/root-domain fallback and no
+    /// page-title or item-name fallback. Either a URL matches exactly, or
+    /// nothing is returned.
+    Strict,
+    /// Like `Default`, but item-name/page-title fallback matching (Priority
+    /// 3, 3b, and 4) is case-insensitive substring matching instead of
+    /// whole-word matching, e.g. "github" matches "GitHub Enterprise".
+    Fuzzy,
 }
 
 /// A credential record for matching.
@@ -55,15 +136,116 @@ pub struct CredentialMatcherInput {
     /// Matching mode
     #[serde(default)]
     pub matching_mode: AutofillMatchingMode,
+    /// Domains (or their registrable root) that must never be offered
+    /// credentials for (e.g. known-phishing or look-alike hosts). When
+    /// `current_url` matches, filtering short-circuits with
+    /// [`PRIORITY_DOMAIN_SUPPRESSED`] before any priority matching runs.
+    #[serde(default)]
+    pub blocked_domains: Vec<String>,
+    /// When non-empty, acts as a strict allowlist gate: `current_url` must
+    /// match one of these domains (or its registrable root), checked before
+    /// any priority matching runs.
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+    /// When set, a caller-selected credential ID to look up directly,
+    /// bypassing URL/title matching entirely - useful when a client already
+    /// knows which entry it wants (e.g. re-confirming a user's selection).
+    #[serde(default)]
+    pub credential_id: Option<String>,
+    /// Username pre-filled on the current login form, if known. When set, it
+    /// acts as a tie-breaker among URL matches: the credential whose
+    /// `username` matches is ordered first within `matched_ids`.
+    #[serde(default)]
+    pub current_username: Option<String>,
+    /// Additional groups of root domains to treat as equivalent for matching
+    /// purposes, e.g. `[["google.com", "youtube.com"]]`, layered on top of a
+    /// small built-in default list (see
+    /// [`equivalent_domains::build_equivalent_domain_groups`]). A credential
+    /// stored for one domain in a group is offered on any other domain in
+    /// that same group, ranked below a same-root-domain match but above
+    /// title/name fallback matching.
+    #[serde(default)]
+    pub equivalent_domains: Vec<Vec<String>>,
+    /// Maximum number of matches to return, across all priorities.
+    #[serde(default = "default_max_results")]
+    pub max_results: usize,
+}
+
+fn default_max_results() -> usize {
+    3
+}
+
+/// `matched_priority` sentinel meaning the current site's domain was
+/// suppressed by `blocked_domains`/`allowed_domains` policy before any
+/// priority matching ran.
+pub const PRIORITY_DOMAIN_SUPPRESSED: u8 = 5;
+
+/// `matched_priority` for a direct credential ID lookup (PRIORITY 0 - the
+/// query parsed as a [`QueryKind::Uuid`]). Checked before any other
+/// priority, but given its own sentinel value rather than `0` since `0`
+/// already means "no match".
+pub const PRIORITY_EXACT_ID_MATCH: u8 = 6;
+
+/// Why a credential matched, for clients that want to render confidence or
+/// explain a result rather than just autofill it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchReason {
+    /// Looked up directly by credential ID, or by a UUID typed into `current_url` (PRIORITY 0).
+    ExactId,
+    /// Stored URL's path is a segment-boundary prefix of the current path (`UrlPathPrefix` mode).
+    PathPrefix,
+    /// Stored URL's domain and port both match the current URL exactly.
+    ExactHostPort,
+    /// Stored URL's domain matches the current URL exactly, ignoring port.
+    ExactHost,
+    /// Stored URL's domain is a subdomain or the registrable root of the current URL's domain.
+    RegistrableDomain,
+    /// Stored URL's root domain and the current URL's root domain are different, but both
+    /// belong to the same configured equivalent-domain group (e.g. `google.com`/`youtube.com`).
+    EquivalentGroup,
+    /// Current URL is an app package name matching a stored URL verbatim.
+    PackageName,
+    /// Page title words matched the credential's item name (credential has no stored URL).
+    TitleWord,
+    /// Words extracted from the current URL matched the credential's item name (no stored URL).
+    UrlWord,
+    /// Free-text search words matched the credential's item name.
+    TextWord,
+}
+
+/// A single scored match: which credential, how confident the match is
+/// (higher `score` is better), and why it matched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoredMatch {
+    pub id: String,
+    pub score: u32,
+    pub reason: MatchReason,
 }
 
 /// Output from credential filtering.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CredentialMatcherOutput {
-    /// IDs of matched credentials (max 3), in priority order
+    /// IDs of matched credentials (max `max_results`), in ranked order.
+    /// Convenience accessor derived from `matches` - kept for callers that
+    /// don't need the score/reason breakdown.
     pub matched_ids: Vec<String>,
-    /// Which priority level matched (1-4, or 0 if no match)
+    /// Which priority level matched (1-4), 0 if no match, or
+    /// [`PRIORITY_DOMAIN_SUPPRESSED`] if the domain was policy-blocked
     pub matched_priority: u8,
+    /// Scored, ranked matches with the reason each one matched. Same order
+    /// and membership as `matched_ids`, just with the ranking rationale kept
+    /// instead of thrown away.
+    #[serde(default)]
+    pub matches: Vec<ScoredMatch>,
+    /// IDs of credentials whose domain looks like a possible typosquat or
+    /// IDN homograph of the current site, but was deliberately NOT
+    /// auto-matched. The UI should warn rather than autofill these.
+    #[serde(default)]
+    pub suspicious_ids: Vec<String>,
+    /// Convenience flag equal to `!suspicious_ids.is_empty()`.
+    #[serde(default)]
+    pub has_suspicious_matches: bool,
 }
 
 /// Internal credential with priority for sorting.
@@ -79,46 +261,109 @@ struct CredentialWithPriority {
 /// * `input` - CredentialMatcherInput containing credentials and search context
 ///
 /// # Returns
-/// CredentialMatcherOutput with filtered credentials (max 3)
+/// CredentialMatcherOutput with filtered, scored credentials (max `input.max_results`)
 pub fn filter_credentials(input: CredentialMatcherInput) -> CredentialMatcherOutput {
     let CredentialMatcherInput {
         credentials,
         current_url,
         page_title,
         matching_mode,
+        blocked_domains,
+        allowed_domains,
+        credential_id,
+        current_username,
+        equivalent_domains,
+        max_results,
     } = input;
 
+    // A caller-selected credential ID bypasses URL/title matching entirely -
+    // it's the same "I already know which one I want" lookup as a UUID
+    // typed into `current_url` (PRIORITY 0), just via an explicit field.
+    if let Some(credential_id) = credential_id {
+        let matches = credentials
+            .iter()
+            .find(|cred| cred.id == credential_id)
+            .map(|cred| {
+                vec![ScoredMatch {
+                    id: cred.id.clone(),
+                    score: u32::MAX,
+                    reason: MatchReason::ExactId,
+                }]
+            })
+            .unwrap_or_default();
+        let matched_priority = if matches.is_empty() { 0 } else { PRIORITY_EXACT_ID_MATCH };
+        return finalize_matches(matches, matched_priority, vec![], false, max_results);
+    }
+
     // Early return for empty URL
     if current_url.is_empty() {
-        return CredentialMatcherOutput {
-            matched_ids: vec![],
-            matched_priority: 0,
-        };
+        return finalize_matches(vec![], 0, vec![], false, max_results);
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════════════
+    // PHISHING POLICY: block/allow list on the current site's root domain
+    // CRITICAL: Enforced before any priority matching (including app package
+    // matching) so a blocked/non-allowed host is never offered a credential.
+    // ═══════════════════════════════════════════════════════════════════════════════
+    if !blocked_domains.is_empty() || !allowed_domains.is_empty() {
+        let current_domain_info = extract_domain_with_port(&current_url);
+        if !current_domain_info.domain.is_empty() {
+            let current_root = extract_root_domain(&current_domain_info.domain);
+            let blocked = is_domain_policy_blocked(
+                &current_domain_info.domain,
+                &current_root,
+                &blocked_domains,
+                &allowed_domains,
+            );
+            if blocked {
+                return finalize_matches(vec![], PRIORITY_DOMAIN_SUPPRESSED, vec![], false, max_results);
+            }
+        }
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════════════
+    // PRIORITY 0: Exact ID Lookup
+    // If the query parses as a UUID, it's a direct credential ID lookup (deep
+    // links, FFI/CLI callers that already know which credential they want)
+    // rather than anything to search for. Classifying it up front also means
+    // the later priorities no longer each have to re-derive what kind of
+    // value `current_url` is.
+    // ═══════════════════════════════════════════════════════════════════════════════
+    let query_kind = parse_query(&current_url);
+    if query_kind == QueryKind::Uuid {
+        if let Some(cred) = credentials.iter().find(|cred| cred.id == current_url) {
+            let matches = vec![ScoredMatch {
+                id: cred.id.clone(),
+                score: u32::MAX,
+                reason: MatchReason::ExactId,
+            }];
+            return finalize_matches(matches, PRIORITY_EXACT_ID_MATCH, vec![], false, max_results);
+        }
     }
 
     // ═══════════════════════════════════════════════════════════════════════════════
     // PRIORITY 1: App Package Name Exact Match
     // Check if current URL is an app package name (e.g., com.coolblue.app)
     // ═══════════════════════════════════════════════════════════════════════════════
-    let is_package_name = is_app_package_name(&current_url);
+    let is_package_name = query_kind == QueryKind::AppPackage;
     if is_package_name {
-        let package_match_ids: Vec<String> = credentials
+        let package_matches: Vec<ScoredMatch> = credentials
             .iter()
             .filter(|cred| {
                 cred.item_urls
                     .iter()
                     .any(|url| !url.is_empty() && url == &current_url)
             })
-            .map(|cred| cred.id.clone())
-            .take(3)
+            .map(|cred| ScoredMatch {
+                id: cred.id.clone(),
+                score: SCORE_PACKAGE_NAME + username_bonus(cred, &current_username),
+                reason: MatchReason::PackageName,
+            })
             .collect();
 
         // EARLY RETURN if matches found
-        if !package_match_ids.is_empty() {
-            return CredentialMatcherOutput {
-                matched_ids: package_match_ids,
-                matched_priority: 1,
-            };
+        if !package_matches.is_empty() {
+            return finalize_matches(package_matches, 1, vec![], false, max_results);
         }
         // If no matches found, skip URL matching and go directly to text matching (Priority 4)
     }
@@ -128,21 +373,29 @@ pub fn filter_credentials(input: CredentialMatcherInput) -> CredentialMatcherOut
     // Try to extract domain from current URL (skip if package name)
     //
     // Sub-priorities within URL matching:
+    //   Priority 0: Path-prefix match (UrlPathPrefix mode, stored URL has a path)
     //   Priority 1: Exact domain+port match (e.g., example.com:8080 == example.com:8080)
     //   Priority 2: Exact domain match (ignoring port) (e.g., example.com:8080 == example.com)
     //   Priority 3: Subdomain/root domain match (e.g., sub.example.com matches example.com)
+    //   Priority 4: Equivalent-domain-group match (e.g., accounts.google.com matches
+    //               youtube.com via a configured equivalent-domain group)
     // ═══════════════════════════════════════════════════════════════════════════════
     if !is_package_name {
         let current_domain_info = extract_domain_with_port(&current_url);
 
         if !current_domain_info.domain.is_empty() {
             let mut filtered: Vec<CredentialWithPriority> = Vec::new();
+            let equivalent_domain_groups = build_equivalent_domain_groups(&equivalent_domains);
 
             // Determine matching features based on mode
             let enable_exact_match = true; // Always enabled
+            // Strict mode wants an exact domain match only, so it's excluded here.
             let enable_subdomain_match = matches!(
                 matching_mode,
-                AutofillMatchingMode::Default | AutofillMatchingMode::UrlSubdomain
+                AutofillMatchingMode::Default
+                    | AutofillMatchingMode::UrlSubdomain
+                    | AutofillMatchingMode::UrlPathPrefix
+                    | AutofillMatchingMode::Fuzzy
             );
 
             // Process credentials with item URLs (check all URLs for each credential)
@@ -165,6 +418,23 @@ pub fn filter_credentials(input: CredentialMatcherInput) -> CredentialMatcherOut
                         continue;
                     }
 
+                    // Check for path-prefix match (priority 0 - highest, UrlPathPrefix mode
+                    // only). A path-scoped credential URL is an all-or-nothing match: if the
+                    // path doesn't prefix-match, it contributes no match at all for this URL,
+                    // even though the bare domain matches - that's the whole point of scoping
+                    // logins to a path.
+                    if matching_mode == AutofillMatchingMode::UrlPathPrefix
+                        && !cred_domain_info.path.is_empty()
+                    {
+                        if current_domain_info.domain == cred_domain_info.domain
+                            && path_prefix_matches(&cred_domain_info.path, &current_domain_info.path)
+                        {
+                            best_priority = Some(0);
+                            break; // Can't do better than a path-prefix match
+                        }
+                        continue;
+                    }
+
                     // Check for exact domain+port match (priority 1 - highest)
                     // Both must have same domain AND same port (or both no port)
                     if enable_exact_match
@@ -187,11 +457,25 @@ pub fn filter_credentials(input: CredentialMatcherInput) -> CredentialMatcherOut
                     // Check for subdomain/root domain match (priority 3)
                     if enable_subdomain_match
                         && domains_match(&current_domain_info.domain, &cred_domain_info.domain)
-                        && best_priority.is_none()
+                        && best_priority.map_or(true, |p| p > 3)
                     {
                         best_priority = Some(3);
                         // Don't break - might find better match in another URL
                     }
+
+                    // Check for equivalent-domain-group match (priority 4 - lowest
+                    // URL tier, gated by the same modes as the subdomain/root check).
+                    if enable_subdomain_match
+                        && best_priority.map_or(true, |p| p > 4)
+                        && equivalent_domains_match(
+                            &current_domain_info.domain,
+                            &cred_domain_info.domain,
+                            &equivalent_domain_groups,
+                        )
+                    {
+                        best_priority = Some(4);
+                        // Don't break - might find better match in another URL
+                    }
                 }
 
                 if let Some(priority) = best_priority {
@@ -202,6 +486,45 @@ pub fn filter_credentials(input: CredentialMatcherInput) -> CredentialMatcherOut
                 }
             }
 
+            // ═══════════════════════════════════════════════════════════════════════
+            // Lookalike/homograph detection (anti-phishing signal, not a match):
+            // for credentials that did NOT match above, flag ones whose domain is a
+            // near-miss typosquat (edit distance <= 2) or an IDN homograph of the
+            // current site, so the UI can warn instead of silently autofilling.
+            // ═══════════════════════════════════════════════════════════════════════
+            let current_root = extract_root_domain(&current_domain_info.domain);
+            let matched_so_far: HashSet<String> =
+                filtered.iter().map(|c| c.credential.id.clone()).collect();
+            let mut suspicious_ids: Vec<String> = Vec::new();
+            if !current_root.is_empty() {
+                let is_current_confusable = is_confusable_host(&current_domain_info.domain);
+                for cred in &credentials {
+                    if matched_so_far.contains(&cred.id) {
+                        continue;
+                    }
+                    let is_suspicious = cred.item_urls.iter().any(|item_url| {
+                        if item_url.is_empty() {
+                            return false;
+                        }
+                        let cred_domain_info = extract_domain_with_port(item_url);
+                        if cred_domain_info.domain.is_empty() {
+                            return false;
+                        }
+                        let cred_root = extract_root_domain(&cred_domain_info.domain);
+                        if cred_root.is_empty() || cred_root == current_root {
+                            return false;
+                        }
+                        is_current_confusable
+                            || levenshtein_distance(&current_root, &cred_root)
+                                <= SUSPICIOUS_DOMAIN_EDIT_DISTANCE
+                    });
+                    if is_suspicious {
+                        suspicious_ids.push(cred.id.clone());
+                    }
+                }
+            }
+            let has_suspicious_matches = !suspicious_ids.is_empty();
+
             // EARLY RETURN if matches found
             if !filtered.is_empty() {
                 // Find the best (lowest) priority level we have
@@ -212,38 +535,49 @@ pub fn filter_credentials(input: CredentialMatcherInput) -> CredentialMatcherOut
                 // - If we have exact domain+port matches (1), we only show those
                 // - If we have exact domain matches (2) but no port matches, we only show those
                 // - If we only have subdomain matches (3), we show those
+                // - If we only have equivalent-domain-group matches (4), we show those
                 let filtered_by_priority: Vec<CredentialWithPriority> = filtered
                     .into_iter()
                     .filter(|c| c.priority == best_priority)
                     .collect();
 
-                // Sort by priority, deduplicate by ID, take first 3
-                let mut sorted = filtered_by_priority;
-                sorted.sort_by_key(|c| c.priority);
-                let mut seen_ids: HashSet<String> = HashSet::new();
-                let unique_ids: Vec<String> = sorted
+                // Score each match by its sub-priority tier, with a username
+                // match breaking ties within the tier.
+                let matches: Vec<ScoredMatch> = filtered_by_priority
                     .into_iter()
-                    .filter(|c| seen_ids.insert(c.credential.id.clone()))
-                    .map(|c| c.credential.id)
-                    .take(3)
+                    .map(|c| {
+                        let (base_score, reason) = match c.priority {
+                            0 => (SCORE_PATH_PREFIX, MatchReason::PathPrefix),
+                            1 => (SCORE_EXACT_HOST_PORT, MatchReason::ExactHostPort),
+                            2 => (SCORE_EXACT_HOST, MatchReason::ExactHost),
+                            3 => (SCORE_REGISTRABLE_DOMAIN, MatchReason::RegistrableDomain),
+                            _ => (SCORE_EQUIVALENT_GROUP, MatchReason::EquivalentGroup),
+                        };
+                        ScoredMatch {
+                            score: base_score + username_bonus(&c.credential, &current_username),
+                            id: c.credential.id,
+                            reason,
+                        }
+                    })
                     .collect();
 
-                return CredentialMatcherOutput {
-                    matched_ids: unique_ids,
-                    matched_priority: 2,
-                };
+                return finalize_matches(matches, 2, suspicious_ids.clone(), has_suspicious_matches, max_results);
             }
 
+            // Strict mode wants URL matches only: no page-title or item-name fallback.
+            let allow_name_fallback = matching_mode != AutofillMatchingMode::Strict;
+            let fuzzy = matching_mode == AutofillMatchingMode::Fuzzy;
+
             // ═══════════════════════════════════════════════════════════════════════════
             // PRIORITY 3: Page Title / Item Name Fallback (Anti-Phishing Protection)
             // No domain matches found - search in item names using page title
             // CRITICAL: Only search credentials with NO URLs defined
             // ═══════════════════════════════════════════════════════════════════════════
-            if !page_title.is_empty() {
+            if allow_name_fallback && !page_title.is_empty() {
                 let title_words = extract_words(&page_title);
 
                 if !title_words.is_empty() {
-                    let name_match_ids: Vec<String> = credentials
+                    let name_matches: Vec<ScoredMatch> = credentials
                         .iter()
                         .filter(|cred| {
                             // SECURITY: Skip credentials that have URLs defined
@@ -255,26 +589,27 @@ pub fn filter_credentials(input: CredentialMatcherInput) -> CredentialMatcherOut
 
                             // Check page title match with item name
                             if let Some(item_name) = &cred.item_name {
-                                let cred_name_words = extract_words(item_name);
-
-                                // Match only complete words, not substrings
-                                title_words.iter().any(|title_word| {
-                                    cred_name_words.iter().any(|cred_word| title_word == cred_word)
-                                })
+                                name_matches_query(&title_words, item_name, fuzzy)
                             } else {
                                 false
                             }
                         })
-                        .map(|cred| cred.id.clone())
-                        .take(3)
+                        .map(|cred| ScoredMatch {
+                            score: SCORE_TITLE_WORD + username_bonus(cred, &current_username),
+                            id: cred.id.clone(),
+                            reason: MatchReason::TitleWord,
+                        })
                         .collect();
 
                     // Return matches from Priority 3 if any found
-                    if !name_match_ids.is_empty() {
-                        return CredentialMatcherOutput {
-                            matched_ids: name_match_ids,
-                            matched_priority: 3,
-                        };
+                    if !name_matches.is_empty() {
+                        return finalize_matches(
+                            name_matches,
+                            3,
+                            suspicious_ids.clone(),
+                            has_suspicious_matches,
+                            max_results,
+                        );
                     }
                 }
             }
@@ -287,8 +622,8 @@ pub fn filter_credentials(input: CredentialMatcherInput) -> CredentialMatcherOut
             // ═══════════════════════════════════════════════════════════════════════════
             let url_words = extract_words(&current_url);
 
-            if !url_words.is_empty() {
-                let url_word_match_ids: Vec<String> = credentials
+            if allow_name_fallback && !url_words.is_empty() {
+                let url_word_matches: Vec<ScoredMatch> = credentials
                     .iter()
                     .filter(|cred| {
                         // SECURITY: Skip credentials that have URLs defined
@@ -299,33 +634,31 @@ pub fn filter_credentials(input: CredentialMatcherInput) -> CredentialMatcherOut
                         }
 
                         if let Some(item_name) = &cred.item_name {
-                            let cred_name_words = extract_words(item_name);
-
-                            // Match only complete words, not substrings
-                            url_words.iter().any(|url_word| {
-                                cred_name_words.iter().any(|cred_word| url_word == cred_word)
-                            })
+                            name_matches_query(&url_words, item_name, fuzzy)
                         } else {
                             false
                         }
                     })
-                    .map(|cred| cred.id.clone())
-                    .take(3)
+                    .map(|cred| ScoredMatch {
+                        score: SCORE_URL_WORD + username_bonus(cred, &current_username),
+                        id: cred.id.clone(),
+                        reason: MatchReason::UrlWord,
+                    })
                     .collect();
 
-                if !url_word_match_ids.is_empty() {
-                    return CredentialMatcherOutput {
-                        matched_ids: url_word_match_ids,
-                        matched_priority: 3,
-                    };
+                if !url_word_matches.is_empty() {
+                    return finalize_matches(
+                        url_word_matches,
+                        3,
+                        suspicious_ids.clone(),
+                        has_suspicious_matches,
+                        max_results,
+                    );
                 }
             }
 
             // No matches found in Priority 2, 3, or 3b
-            return CredentialMatcherOutput {
-                matched_ids: vec![],
-                matched_priority: 0,
-            };
+            return finalize_matches(vec![], 0, suspicious_ids, has_suspicious_matches, max_results);
         }
     }
 
@@ -337,37 +670,50 @@ pub fn filter_credentials(input: CredentialMatcherInput) -> CredentialMatcherOut
     let search_words = extract_words(&current_url);
 
     if !search_words.is_empty() {
-        let text_match_ids: Vec<String> = credentials
+        let fuzzy = matching_mode == AutofillMatchingMode::Fuzzy;
+        let text_matches: Vec<ScoredMatch> = credentials
             .iter()
             .filter(|cred| {
                 if let Some(item_name) = &cred.item_name {
-                    let item_name_words = extract_words(item_name);
-
-                    // Check if any search word matches any item name word exactly
-                    search_words
-                        .iter()
-                        .any(|search_word| item_name_words.contains(search_word))
+                    name_matches_query(&search_words, item_name, fuzzy)
                 } else {
                     false
                 }
             })
-            .map(|cred| cred.id.clone())
-            .take(3)
+            .map(|cred| ScoredMatch {
+                score: SCORE_TEXT_WORD + username_bonus(cred, &current_username),
+                id: cred.id.clone(),
+                reason: MatchReason::TextWord,
+            })
             .collect();
 
-        if !text_match_ids.is_empty() {
-            return CredentialMatcherOutput {
-                matched_ids: text_match_ids,
-                matched_priority: 4,
-            };
+        if !text_matches.is_empty() {
+            return finalize_matches(text_matches, 4, vec![], false, max_results);
         }
     }
 
     // No matches found
-    CredentialMatcherOutput {
-        matched_ids: vec![],
-        matched_priority: 0,
+    finalize_matches(vec![], 0, vec![], false, max_results)
+}
+
+/// Returns true if `domain` (or its registrable `root`) is blocked by policy:
+/// either one is on the blocklist, or an allowlist is configured and neither
+/// one is on it.
+fn is_domain_policy_blocked(
+    domain: &str,
+    root: &str,
+    blocked_domains: &[String],
+    allowed_domains: &[String],
+) -> bool {
+    let is_listed = |list: &[String]| list.iter().any(|d| d == domain || d == root);
+
+    if is_listed(blocked_domains) {
+        return true;
+    }
+    if !allowed_domains.is_empty() && !is_listed(allowed_domains) {
+        return true;
     }
+    false
 }
 
 /// Extract meaningful words from text, removing punctuation and filtering stop words.
@@ -395,6 +741,23 @@ fn extract_words(text: &str) -> Vec<String> {
         .collect()
 }
 
+/// Returns true if any of `query_words` matches `item_name`.
+///
+/// In `fuzzy` mode this is a case-insensitive substring match (e.g. "github"
+/// matches "GitHub Enterprise"); otherwise a query word must match one of
+/// `item_name`'s words completely, not as a substring.
+fn name_matches_query(query_words: &[String], item_name: &str, fuzzy: bool) -> bool {
+    if fuzzy {
+        let item_name_lower = item_name.to_lowercase();
+        return query_words
+            .iter()
+            .any(|word| item_name_lower.contains(word.as_str()));
+    }
+
+    let item_name_words = extract_words(item_name);
+    query_words.iter().any(|word| item_name_words.contains(word))
+}
+
 /// Filter credentials from JSON input (convenience function for FFI).
 pub fn filter_credentials_json(input_json: &str) -> Result<String, String> {
     let input: CredentialMatcherInput =
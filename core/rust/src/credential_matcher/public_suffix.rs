@@ -0,0 +1,321 @@
+//! Public Suffix List (PSL) engine.
+//!
+//! Parses the embedded PSL data (ICANN + PRIVATE sections) into a rule table and
+//! implements the standard public suffix matching algorithm:
+//! <https://github.com/publicsuffix/list/wiki/Format#algorithm>
+//!
+//! 1. Split the host into labels.
+//! 2. Find the rule with the most labels that matches, where a plain rule matches
+//!    literally, a wildcard rule (`*.ck`) matches any single label in that
+//!    position, and an exception rule (`!www.ck`) beats a wildcard match of the
+//!    same length and shortens the resulting suffix by one label.
+//! 3. If nothing matches, the rightmost label itself is the public suffix.
+//!
+//! # Refreshing `public_suffix_list.dat`
+//!
+//! `public_suffix_list.dat` is a curated subset of the upstream list at
+//! [`PSL_SOURCE_URL`], not a verbatim copy - we only keep the rule shapes
+//! (plain, wildcard, exception, ICANN + PRIVATE) needed to classify the
+//! domains AliasVault cares about, so this stays a small, offline, embedded
+//! file rather than a network dependency. To refresh it: download the
+//! current upstream list, re-curate the subset (keeping the `===BEGIN/END
+//! ICANN/PRIVATE DOMAINS===` markers and comment style), and extend
+//! `psl_tests.txt` with any new vectors worth locking in - the conformance
+//! harness in `psl_conformance.rs` runs it against [`registrable_domain`].
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Raw PSL data, embedded into the binary at compile time.
+const PSL_DATA: &str = include_str!("public_suffix_list.dat");
+
+/// Upstream source of truth for `public_suffix_list.dat`. See "Refreshing
+/// `public_suffix_list.dat`" above for the update procedure.
+pub const PSL_SOURCE_URL: &str = "https://publicsuffix.org/list/public_suffix_list.dat";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RuleKind {
+    Normal,
+    Wildcard,
+    Exception,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Rule {
+    kind: RuleKind,
+    /// Number of labels in the rule (the wildcard's `*` counts as one label).
+    label_count: usize,
+    icann: bool,
+}
+
+/// Parsed public suffix rule table, keyed by the rule's labels joined
+/// right-to-left with `.` (e.g. the rule `co.uk` is keyed as `"uk.co"`, and the
+/// wildcard rule `*.kobe.jp` is keyed as `"jp.kobe.*"`).
+struct PublicSuffixList {
+    rules: HashMap<String, Rule>,
+}
+
+fn rule_key(labels_left_to_right: &[&str]) -> String {
+    labels_left_to_right
+        .iter()
+        .rev()
+        .copied()
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+impl PublicSuffixList {
+    fn parse(data: &str) -> Self {
+        let mut rules = HashMap::new();
+        let mut icann = true; // Assume ICANN until a PRIVATE marker is seen.
+
+        for line in data.lines() {
+            let line = line.trim();
+
+            if line.contains("BEGIN PRIVATE DOMAINS") {
+                icann = false;
+                continue;
+            }
+            if line.contains("BEGIN ICANN DOMAINS") {
+                icann = true;
+                continue;
+            }
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+
+            let (kind, rule_text) = if let Some(stripped) = line.strip_prefix('!') {
+                (RuleKind::Exception, stripped)
+            } else if line.starts_with("*.") {
+                (RuleKind::Wildcard, line)
+            } else {
+                (RuleKind::Normal, line)
+            };
+
+            let labels: Vec<&str> = rule_text.split('.').collect();
+            let key = rule_key(&labels);
+
+            rules.insert(
+                key,
+                Rule {
+                    kind,
+                    label_count: labels.len(),
+                    icann,
+                },
+            );
+        }
+
+        Self { rules }
+    }
+
+    /// Returns the matching public suffix (number of trailing labels) and
+    /// whether the winning rule came from the ICANN section.
+    fn find_suffix_len(&self, labels: &[&str], icann_only: bool) -> (usize, bool) {
+        let n = labels.len();
+        let reversed: Vec<&str> = labels.iter().rev().copied().collect();
+
+        let mut best_exception: Option<(usize, bool)> = None;
+        let mut best_match: Option<(usize, bool)> = None;
+
+        for k in 1..=n {
+            let suffix_rev = &reversed[..k];
+
+            // Literal match (covers both Normal and Exception rules).
+            let literal_key = suffix_rev.join(".");
+            if let Some(rule) = self.rules.get(&literal_key) {
+                if !icann_only || rule.icann {
+                    match rule.kind {
+                        RuleKind::Exception => {
+                            if best_exception.map_or(true, |(len, _)| k > len) {
+                                best_exception = Some((k, rule.icann));
+                            }
+                        }
+                        RuleKind::Normal => {
+                            if best_match.map_or(true, |(len, _)| k > len) {
+                                best_match = Some((k, rule.icann));
+                            }
+                        }
+                        RuleKind::Wildcard => {}
+                    }
+                }
+            }
+
+            // Wildcard match: same suffix with its leftmost (most specific) label
+            // replaced by "*".
+            if k >= 1 {
+                let mut wildcard_rev = suffix_rev.to_vec();
+                *wildcard_rev.last_mut().unwrap() = "*";
+                let wildcard_key = wildcard_rev.join(".");
+                if let Some(rule) = self.rules.get(&wildcard_key) {
+                    if rule.kind == RuleKind::Wildcard && (!icann_only || rule.icann) {
+                        if best_match.map_or(true, |(len, _)| k > len) {
+                            best_match = Some((k, rule.icann));
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some((len, icann)) = best_exception {
+            // An exception rule's public suffix is the rule itself minus its
+            // leftmost label.
+            return (len - 1, icann);
+        }
+
+        if let Some((len, icann)) = best_match {
+            return (len, icann);
+        }
+
+        // No rule matched at all: fall back to treating the rightmost label as
+        // the public suffix (the implicit "*" rule).
+        (1, true)
+    }
+}
+
+static PARSED_PSL: OnceLock<PublicSuffixList> = OnceLock::new();
+
+fn psl() -> &'static PublicSuffixList {
+    PARSED_PSL.get_or_init(|| PublicSuffixList::parse(PSL_DATA))
+}
+
+/// Returns the public suffix of `domain` (e.g. `"co.uk"` for `"sub.example.co.uk"`),
+/// and whether the match came from the ICANN section.
+///
+/// When `icann_only` is true, PRIVATE section rules (e.g. `github.io`) are
+/// ignored, so `"foo.github.io"` is treated as if `github.io` were a regular
+/// domain rather than a public suffix.
+pub fn public_suffix(domain: &str, icann_only: bool) -> Option<String> {
+    if domain.is_empty() {
+        return None;
+    }
+
+    let labels: Vec<&str> = domain.split('.').filter(|l| !l.is_empty()).collect();
+    if labels.is_empty() {
+        return None;
+    }
+
+    let (suffix_len, _icann) = psl().find_suffix_len(&labels, icann_only);
+    let suffix_len = suffix_len.min(labels.len());
+    Some(labels[labels.len() - suffix_len..].join("."))
+}
+
+/// Returns the registrable ("root") domain for `domain`, i.e. the public
+/// suffix plus exactly one additional label to its left.
+///
+/// Returns an empty string if `domain` is itself a public suffix (e.g.
+/// `"co.uk"`) or has no registrable part.
+pub fn registrable_domain(domain: &str, icann_only: bool) -> String {
+    let labels: Vec<&str> = domain.split('.').filter(|l| !l.is_empty()).collect();
+    if labels.is_empty() {
+        return String::new();
+    }
+
+    let (suffix_len, _icann) = psl().find_suffix_len(&labels, icann_only);
+
+    if suffix_len >= labels.len() {
+        // The whole host is (at most) a public suffix - no registrable domain.
+        return String::new();
+    }
+
+    labels[labels.len() - suffix_len - 1..].join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_rule() {
+        assert_eq!(registrable_domain("example.com", false), "example.com");
+        assert_eq!(registrable_domain("sub.example.com", false), "example.com");
+    }
+
+    #[test]
+    fn test_two_level_rule() {
+        assert_eq!(registrable_domain("example.co.uk", false), "example.co.uk");
+        assert_eq!(registrable_domain("sub.example.co.uk", false), "example.co.uk");
+    }
+
+    #[test]
+    fn test_wildcard_rule() {
+        // *.ck with no exception: "a.b.ck" -> public suffix "b.ck", root "a.b.ck"
+        assert_eq!(registrable_domain("a.b.ck", false), "a.b.ck");
+    }
+
+    #[test]
+    fn test_exception_rule_wins_over_wildcard() {
+        // "!www.ck" is an exception to "*.ck": www.ck itself is registrable.
+        assert_eq!(registrable_domain("www.ck", false), "www.ck");
+        assert_eq!(registrable_domain("foo.www.ck", false), "www.ck");
+    }
+
+    #[test]
+    fn test_kobe_jp_exception() {
+        // "*.kobe.jp" is a public suffix, but "!city.kobe.jp" is an exception,
+        // so city.kobe.jp is itself registrable.
+        assert_eq!(registrable_domain("city.kobe.jp", false), "city.kobe.jp");
+        assert_eq!(registrable_domain("sub.city.kobe.jp", false), "city.kobe.jp");
+        // Without the exception, any other *.kobe.jp label is a public suffix.
+        assert_eq!(registrable_domain("example.kobe.jp", false), "example.kobe.jp");
+        assert_eq!(registrable_domain("www.example.kobe.jp", false), "example.kobe.jp");
+    }
+
+    #[test]
+    fn test_host_is_itself_a_public_suffix() {
+        assert_eq!(registrable_domain("co.uk", false), "");
+        assert_eq!(registrable_domain("com", false), "");
+        assert_eq!(registrable_domain("kobe.jp", false), "");
+    }
+
+    #[test]
+    fn test_private_section_github_io() {
+        assert_eq!(registrable_domain("example.github.io", false), "example.github.io");
+        assert_eq!(registrable_domain("foo.example.github.io", false), "example.github.io");
+
+        // Two unrelated sites under the same private suffix must not collapse
+        // to the same root domain.
+        assert_ne!(
+            registrable_domain("alice.github.io", false),
+            registrable_domain("bob.github.io", false)
+        );
+    }
+
+    #[test]
+    fn test_icann_only_ignores_private_section() {
+        // With icann_only, github.io is not a recognized public suffix, so it
+        // falls back to the implicit rule (last label = "io").
+        assert_eq!(registrable_domain("example.github.io", true), "github.io");
+    }
+
+    #[test]
+    fn test_aws_compute_wildcard() {
+        assert_eq!(
+            registrable_domain("ec2-1-2-3-4.compute.amazonaws.com", false),
+            "ec2-1-2-3-4.compute.amazonaws.com"
+        );
+    }
+
+    #[test]
+    fn test_aws_s3_bucket_suffix() {
+        // s3.amazonaws.com is itself a public suffix, so each bucket gets its
+        // own registrable domain.
+        assert_eq!(
+            registrable_domain("mybucket.s3.amazonaws.com", false),
+            "mybucket.s3.amazonaws.com"
+        );
+        assert_ne!(
+            registrable_domain("alice-bucket.s3.amazonaws.com", false),
+            registrable_domain("bob-bucket.s3.amazonaws.com", false)
+        );
+    }
+
+    #[test]
+    fn test_fallback_for_unknown_tld() {
+        // Not in our curated list - falls back to treating the last label as
+        // the public suffix.
+        assert_eq!(registrable_domain("example.unknowntld", false), "example.unknowntld");
+    }
+}
+
+#[cfg(test)]
+mod psl_conformance;
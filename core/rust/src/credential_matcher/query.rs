@@ -0,0 +1,96 @@
+//! Typed classification of the `current_url` search query.
+//!
+//! `filter_credentials` used to re-derive what kind of value it was holding
+//! (app package, URL, or free text) at each priority branch separately. This
+//! classifies the query once, up front, into a [`QueryKind`].
+
+use super::domain::is_app_package_name;
+use super::extract_domain_with_port;
+
+/// What kind of value a raw query string (the matcher's `current_url` field)
+/// turned out to be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryKind {
+    /// A canonical UUID - almost certainly a credential ID, for direct ID lookup.
+    Uuid,
+    /// An Android/iOS app package name (e.g. `com.coolblue.app`).
+    AppPackage,
+    /// A URL (or bare domain) a registrable domain could be extracted from.
+    Url,
+    /// Free text - page title or item name search terms.
+    Text,
+}
+
+/// Classify `query` into a [`QueryKind`].
+///
+/// The checks are hierarchical rather than independent pattern matches: a
+/// UUID never also looks like an app package or extracts a domain, so
+/// whichever check passes first wins.
+pub fn parse_query(query: &str) -> QueryKind {
+    if is_uuid(query) {
+        return QueryKind::Uuid;
+    }
+    if is_app_package_name(query) {
+        return QueryKind::AppPackage;
+    }
+    if !extract_domain_with_port(query).domain.is_empty() {
+        return QueryKind::Url;
+    }
+    QueryKind::Text
+}
+
+/// Returns true if `s` is a canonical, hyphenated 8-4-4-4-12 hex UUID,
+/// case-insensitively. This is a format check only - it doesn't validate
+/// version/variant bits, since credential IDs minted by the different
+/// platforms (browser, iOS, Android, .NET) aren't guaranteed to all be v4.
+fn is_uuid(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    if bytes.len() != 36 {
+        return false;
+    }
+
+    bytes.iter().enumerate().all(|(i, b)| {
+        if matches!(i, 8 | 13 | 18 | 23) {
+            *b == b'-'
+        } else {
+            b.is_ascii_hexdigit()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_uuid() {
+        assert!(is_uuid("550e8400-e29b-41d4-a716-446655440000"));
+        assert!(is_uuid("550E8400-E29B-41D4-A716-446655440000"));
+        assert!(!is_uuid("550e8400-e29b-41d4-a716-44665544000")); // too short
+        assert!(!is_uuid("550e8400ze29b41d4a716446655440000")); // no hyphens
+        assert!(!is_uuid("com.coolblue.app"));
+    }
+
+    #[test]
+    fn test_parse_query_uuid() {
+        assert_eq!(
+            parse_query("550e8400-e29b-41d4-a716-446655440000"),
+            QueryKind::Uuid
+        );
+    }
+
+    #[test]
+    fn test_parse_query_app_package() {
+        assert_eq!(parse_query("com.coolblue.app"), QueryKind::AppPackage);
+    }
+
+    #[test]
+    fn test_parse_query_url() {
+        assert_eq!(parse_query("https://github.com/login"), QueryKind::Url);
+    }
+
+    #[test]
+    fn test_parse_query_text() {
+        assert_eq!(parse_query("find my github login"), QueryKind::Text);
+    }
+}
@@ -6,11 +6,11 @@ use super::*;
 fn create_test_credential(service_name: &str, service_url: &str, username: &str) -> Credential {
     Credential {
         id: uuid_v4(),
-        service_name: Some(service_name.to_string()),
-        service_url: if service_url.is_empty() {
-            None
+        item_name: Some(service_name.to_string()),
+        item_urls: if service_url.is_empty() {
+            vec![]
         } else {
-            Some(service_url.to_string())
+            vec![service_url.to_string()]
         },
         username: if username.is_empty() {
             None
@@ -54,6 +54,12 @@ fn filter(credentials: Vec<Credential>, current_url: &str, page_title: &str) ->
         current_url: current_url.to_string(),
         page_title: page_title.to_string(),
         matching_mode: AutofillMatchingMode::Default,
+        blocked_domains: vec![],
+        allowed_domains: vec![],
+        credential_id: None,
+        current_username: None,
+        equivalent_domains: vec![],
+        max_results: default_max_results(),
     };
     let output = filter_credentials(input);
 
@@ -75,7 +81,7 @@ fn test_exact_url_match() {
     let matches = filter(credentials, "www.coolblue.nl", "");
 
     assert_eq!(matches.len(), 1);
-    assert_eq!(matches[0].service_name.as_deref(), Some("Coolblue"));
+    assert_eq!(matches[0].item_name.as_deref(), Some("Coolblue"));
 }
 
 /// [#2] - Base URL with path match
@@ -85,7 +91,7 @@ fn test_base_url_with_path_match() {
     let matches = filter(credentials, "https://gmail.com/signin", "");
 
     assert_eq!(matches.len(), 1);
-    assert_eq!(matches[0].service_name.as_deref(), Some("Gmail"));
+    assert_eq!(matches[0].item_name.as_deref(), Some("Gmail"));
 }
 
 /// [#3] - Root domain with subdomain match
@@ -95,7 +101,7 @@ fn test_root_domain_with_subdomain_match() {
     let matches = filter(credentials, "https://mail.google.com", "");
 
     assert_eq!(matches.len(), 1);
-    assert_eq!(matches[0].service_name.as_deref(), Some("Google"));
+    assert_eq!(matches[0].item_name.as_deref(), Some("Google"));
 }
 
 /// [#4] - No matches for non-existent domain
@@ -114,7 +120,7 @@ fn test_partial_url_matches_full_url() {
     let matches = filter(credentials, "https://www.dumpert.nl", "");
 
     assert_eq!(matches.len(), 1);
-    assert_eq!(matches[0].service_name.as_deref(), Some("Dumpert"));
+    assert_eq!(matches[0].item_name.as_deref(), Some("Dumpert"));
 }
 
 /// [#6] - Full URL stored matches partial URL search
@@ -124,7 +130,7 @@ fn test_full_url_matches_partial_url() {
     let matches = filter(credentials, "coolblue.nl", "");
 
     assert_eq!(matches.len(), 1);
-    assert_eq!(matches[0].service_name.as_deref(), Some("Coolblue"));
+    assert_eq!(matches[0].item_name.as_deref(), Some("Coolblue"));
 }
 
 /// [#7] - Protocol variations (http/https/none) match
@@ -139,9 +145,9 @@ fn test_protocol_variations() {
     assert_eq!(https_matches.len(), 1);
     assert_eq!(http_matches.len(), 1);
     assert_eq!(no_protocol_matches.len(), 1);
-    assert_eq!(https_matches[0].service_name.as_deref(), Some("GitHub"));
-    assert_eq!(http_matches[0].service_name.as_deref(), Some("GitHub"));
-    assert_eq!(no_protocol_matches[0].service_name.as_deref(), Some("GitHub"));
+    assert_eq!(https_matches[0].item_name.as_deref(), Some("GitHub"));
+    assert_eq!(http_matches[0].item_name.as_deref(), Some("GitHub"));
+    assert_eq!(no_protocol_matches[0].item_name.as_deref(), Some("GitHub"));
 }
 
 /// [#8] - WWW prefix variations match
@@ -154,8 +160,8 @@ fn test_www_variations() {
 
     assert_eq!(with_www.len(), 1);
     assert_eq!(without_www.len(), 1);
-    assert_eq!(with_www[0].service_name.as_deref(), Some("Dumpert"));
-    assert_eq!(without_www[0].service_name.as_deref(), Some("Dumpert"));
+    assert_eq!(with_www[0].item_name.as_deref(), Some("Dumpert"));
+    assert_eq!(without_www[0].item_name.as_deref(), Some("Dumpert"));
 }
 
 /// [#9] - Subdomain matching
@@ -168,11 +174,11 @@ fn test_subdomain_matching() {
     let no_subdomain = filter(credentials, "https://example.com", "");
 
     assert_eq!(app_subdomain.len(), 1);
-    assert_eq!(app_subdomain[0].service_name.as_deref(), Some("Subdomain Example"));
+    assert_eq!(app_subdomain[0].item_name.as_deref(), Some("Subdomain Example"));
     assert_eq!(www_subdomain.len(), 1);
-    assert_eq!(www_subdomain[0].service_name.as_deref(), Some("Subdomain Example"));
+    assert_eq!(www_subdomain[0].item_name.as_deref(), Some("Subdomain Example"));
     assert_eq!(no_subdomain.len(), 1);
-    assert_eq!(no_subdomain[0].service_name.as_deref(), Some("Subdomain Example"));
+    assert_eq!(no_subdomain[0].item_name.as_deref(), Some("Subdomain Example"));
 }
 
 /// [#10] - Paths and query strings ignored
@@ -185,11 +191,11 @@ fn test_paths_and_query_strings_ignored() {
     let with_fragment = filter(credentials, "https://gmail.com#inbox", "");
 
     assert_eq!(with_path.len(), 1);
-    assert_eq!(with_path[0].service_name.as_deref(), Some("GitHub"));
+    assert_eq!(with_path[0].item_name.as_deref(), Some("GitHub"));
     assert_eq!(with_query.len(), 1);
-    assert_eq!(with_query[0].service_name.as_deref(), Some("Stack Overflow"));
+    assert_eq!(with_query[0].item_name.as_deref(), Some("Stack Overflow"));
     assert_eq!(with_fragment.len(), 1);
-    assert_eq!(with_fragment[0].service_name.as_deref(), Some("Gmail"));
+    assert_eq!(with_fragment[0].item_name.as_deref(), Some("Gmail"));
 }
 
 /// [#11] - Complex URL variations
@@ -199,7 +205,7 @@ fn test_complex_url_variations() {
     let matches = filter(credentials, "https://www.coolblue.nl/product/12345?ref=google", "");
 
     assert_eq!(matches.len(), 1);
-    assert_eq!(matches[0].service_name.as_deref(), Some("Coolblue"));
+    assert_eq!(matches[0].item_name.as_deref(), Some("Coolblue"));
 }
 
 /// [#12] - Priority ordering
@@ -209,7 +215,7 @@ fn test_priority_ordering() {
     let matches = filter(credentials, "coolblue.nl", "");
 
     assert_eq!(matches.len(), 1);
-    assert_eq!(matches[0].service_name.as_deref(), Some("Coolblue"));
+    assert_eq!(matches[0].item_name.as_deref(), Some("Coolblue"));
 }
 
 /// [#13] - Title-only matching
@@ -219,7 +225,7 @@ fn test_title_only_matching() {
     let matches = filter(credentials, "https://nomatch.com", "newyorktimes");
 
     assert_eq!(matches.len(), 1);
-    assert_eq!(matches[0].service_name.as_deref(), Some("Title Only newyorktimes"));
+    assert_eq!(matches[0].item_name.as_deref(), Some("Title Only newyorktimes"));
 }
 
 /// [#14] - Domain name part matching
@@ -239,7 +245,7 @@ fn test_package_name_matching() {
     let matches = filter(credentials, "com.coolblue.app", "");
 
     assert_eq!(matches.len(), 1);
-    assert_eq!(matches[0].service_name.as_deref(), Some("Coolblue App"));
+    assert_eq!(matches[0].item_name.as_deref(), Some("Coolblue App"));
 }
 
 /// [#16] - Invalid URL handling
@@ -279,7 +285,7 @@ fn test_separators_and_punctuation_stripped() {
 
     // Should match "Reddit" even though it's followed by a comma and description
     assert_eq!(matches.len(), 1);
-    assert_eq!(matches[0].service_name.as_deref(), Some("Reddit"));
+    assert_eq!(matches[0].item_name.as_deref(), Some("Reddit"));
 }
 
 /// [#20] - Test reversed domain (app package name) doesn't match on TLD
@@ -294,7 +300,7 @@ fn test_reversed_domain_no_tld_match() {
 
     // Should only match Marktplaats, not Dumpert (even though both have "nl")
     assert_eq!(matches.len(), 1);
-    assert_eq!(matches[0].service_name.as_deref(), Some("Marktplaats.nl"));
+    assert_eq!(matches[0].item_name.as_deref(), Some("Marktplaats.nl"));
 }
 
 /// [#21] - Test app package names are properly detected and handled
@@ -310,17 +316,17 @@ fn test_app_package_names_handling() {
     // Test com.google.android package matches
     let google_matches = filter(credentials.clone(), "com.google.android.googlequicksearchbox", "");
     assert_eq!(google_matches.len(), 1);
-    assert_eq!(google_matches[0].service_name.as_deref(), Some("Google App"));
+    assert_eq!(google_matches[0].item_name.as_deref(), Some("Google App"));
 
     // Test com.facebook package matches
     let facebook_matches = filter(credentials.clone(), "com.facebook.katana", "");
     assert_eq!(facebook_matches.len(), 1);
-    assert_eq!(facebook_matches[0].service_name.as_deref(), Some("Facebook"));
+    assert_eq!(facebook_matches[0].item_name.as_deref(), Some("Facebook"));
 
     // Test that web domain doesn't match package name
     let web_matches = filter(credentials, "https://example.com", "");
     assert_eq!(web_matches.len(), 1);
-    assert_eq!(web_matches[0].service_name.as_deref(), Some("Generic Site"));
+    assert_eq!(web_matches[0].item_name.as_deref(), Some("Generic Site"));
 }
 
 /// [#22] - Test multi-part TLDs like .com.au don't match incorrectly
@@ -336,17 +342,17 @@ fn test_multi_part_tlds() {
     // Test that blabla.blabla.com.au doesn't match other .com.au sites
     let blabla_matches = filter(credentials.clone(), "https://blabla.blabla.com.au", "");
     assert_eq!(blabla_matches.len(), 1);
-    assert_eq!(blabla_matches[0].service_name.as_deref(), Some("BlaBla AU"));
+    assert_eq!(blabla_matches[0].item_name.as_deref(), Some("BlaBla AU"));
 
     // Test that example.com.au doesn't match blabla.blabla.com.au
     let example_matches = filter(credentials.clone(), "https://example.com.au", "");
     assert_eq!(example_matches.len(), 1);
-    assert_eq!(example_matches[0].service_name.as_deref(), Some("Example Site AU"));
+    assert_eq!(example_matches[0].item_name.as_deref(), Some("Example Site AU"));
 
     // Test that .co.uk domains work correctly too
     let uk_matches = filter(credentials, "https://example.co.uk", "");
     assert_eq!(uk_matches.len(), 1);
-    assert_eq!(uk_matches[0].service_name.as_deref(), Some("UK Site"));
+    assert_eq!(uk_matches[0].item_name.as_deref(), Some("UK Site"));
 }
 
 /// Test JSON serialization/deserialization
@@ -358,6 +364,12 @@ fn test_json_roundtrip() {
         current_url: "https://github.com".to_string(),
         page_title: String::new(),
         matching_mode: AutofillMatchingMode::Default,
+        blocked_domains: vec![],
+        allowed_domains: vec![],
+        credential_id: None,
+        current_username: None,
+        equivalent_domains: vec![],
+        max_results: default_max_results(),
     };
 
     let json = serde_json::to_string(&input).unwrap();
@@ -367,7 +379,7 @@ fn test_json_roundtrip() {
     assert_eq!(output.matched_ids.len(), 1);
     // Look up the credential by ID to verify it's GitHub
     let matched = credentials.iter().find(|c| c.id == output.matched_ids[0]).unwrap();
-    assert_eq!(matched.service_name.as_deref(), Some("GitHub"));
+    assert_eq!(matched.item_name.as_deref(), Some("GitHub"));
 }
 
 /// Test empty URL returns empty results
@@ -396,6 +408,116 @@ fn test_max_three_results() {
     assert!(matches.len() <= 3);
 }
 
+/// Helper to build a [`CredentialMatcherInput`] against the current field
+/// shape, for tests that need fields `filter`/`create_test_credential` above
+/// don't thread through (those helpers predate `item_urls`/`equivalent_domains`).
+fn filter_with_equivalent_domains(
+    credentials: Vec<Credential>,
+    current_url: &str,
+    equivalent_domains: Vec<Vec<String>>,
+) -> CredentialMatcherOutput {
+    filter_credentials(CredentialMatcherInput {
+        credentials,
+        current_url: current_url.to_string(),
+        page_title: String::new(),
+        matching_mode: AutofillMatchingMode::Default,
+        blocked_domains: vec![],
+        allowed_domains: vec![],
+        credential_id: None,
+        current_username: None,
+        equivalent_domains,
+        max_results: default_max_results(),
+    })
+}
+
+/// [#24] - Default equivalent-domain groups (e.g. google.com/youtube.com) match
+#[test]
+fn test_equivalent_domain_group_default() {
+    let credentials = vec![Credential {
+        id: "cred-google".to_string(),
+        item_name: Some("Google".to_string()),
+        item_urls: vec!["https://accounts.google.com".to_string()],
+        username: None,
+    }];
+
+    let output = filter_with_equivalent_domains(credentials, "https://www.youtube.com", vec![]);
+
+    assert_eq!(output.matched_ids, vec!["cred-google".to_string()]);
+    assert_eq!(output.matches[0].reason, MatchReason::EquivalentGroup);
+}
+
+/// [#25] - User-supplied equivalent-domain groups match, and rank below a root-domain match
+#[test]
+fn test_equivalent_domain_group_custom_ranks_below_root_domain_match() {
+    let credentials = vec![
+        Credential {
+            id: "cred-equivalent".to_string(),
+            item_name: Some("MyBrand US".to_string()),
+            item_urls: vec!["https://mybrand.com".to_string()],
+            username: None,
+        },
+        Credential {
+            id: "cred-exact".to_string(),
+            item_name: Some("MyBrand IO".to_string()),
+            item_urls: vec!["https://app.mybrand.io".to_string()],
+            username: None,
+        },
+    ];
+    let equivalent_domains = vec![vec!["mybrand.com".to_string(), "mybrand.io".to_string()]];
+
+    let output = filter_with_equivalent_domains(credentials, "https://app.mybrand.io", equivalent_domains);
+
+    // The root-domain match wins outright; the equivalent-group match is a
+    // lower priority tier and is excluded once a better match exists.
+    assert_eq!(output.matched_ids, vec!["cred-exact".to_string()]);
+}
+
+/// [#26] - Unconfigured domains never match via the equivalent-domain-group tier
+#[test]
+fn test_equivalent_domain_group_no_match_without_configured_group() {
+    let credentials = vec![Credential {
+        id: "cred".to_string(),
+        item_name: Some("Unrelated".to_string()),
+        item_urls: vec!["https://unrelated-a.com".to_string()],
+        username: None,
+    }];
+
+    let output = filter_with_equivalent_domains(credentials, "https://unrelated-b.com", vec![]);
+
+    assert!(output.matched_ids.is_empty());
+}
+
+/// A credential whose first `item_url` only reaches the equivalent-domain-group
+/// tier (priority 4) must still upgrade to a later `item_url`'s better
+/// subdomain/root match (priority 3) instead of getting stuck at 4.
+#[test]
+fn test_multiple_item_urls_upgrade_from_equivalent_group_to_subdomain_match() {
+    let credentials = vec![
+        Credential {
+            id: "cred-multi-url".to_string(),
+            item_name: Some("MyBrand Multi".to_string()),
+            item_urls: vec!["https://unrelated-x.com".to_string(), "https://sub.mybrand.io".to_string()],
+            username: None,
+        },
+        Credential {
+            id: "cred-subdomain".to_string(),
+            item_name: Some("MyBrand Other".to_string()),
+            item_urls: vec!["https://other.mybrand.io".to_string()],
+            username: None,
+        },
+    ];
+    let equivalent_domains = vec![vec!["unrelated-x.com".to_string(), "mybrand.io".to_string()]];
+
+    let output = filter_with_equivalent_domains(credentials, "https://app.mybrand.io", equivalent_domains);
+
+    // Both credentials land at the subdomain/root tier (3); if "cred-multi-url"
+    // were stuck at its first URL's tier (4), it would have been dropped once
+    // the global best priority across all credentials settled at 3.
+    let mut matched_ids = output.matched_ids;
+    matched_ids.sort();
+    assert_eq!(matched_ids, vec!["cred-multi-url".to_string(), "cred-subdomain".to_string()]);
+}
+
 /// [#23] - E2E test scenario: credentials with URLs should only match their specific domains
 /// This mirrors the browser extension E2E test setup
 #[test]
@@ -412,23 +534,23 @@ fn test_e2e_scenario_url_only_matching() {
 
     // Test 2: example.com should only match Example Site (and possibly subdomain due to root domain matching)
     let example_matches = filter(credentials.clone(), "https://example.com/login", "E2E Test Form");
-    println!("example.com matches: {:?}", example_matches.iter().map(|c| c.service_name.as_deref()).collect::<Vec<_>>());
+    println!("example.com matches: {:?}", example_matches.iter().map(|c| c.item_name.as_deref()).collect::<Vec<_>>());
     assert!(example_matches.len() >= 1, "example.com should match at least one credential");
-    assert!(example_matches.iter().any(|c| c.service_name.as_deref() == Some("Example Site")),
+    assert!(example_matches.iter().any(|c| c.item_name.as_deref() == Some("Example Site")),
         "example.com should match Example Site");
-    assert!(!example_matches.iter().any(|c| c.service_name.as_deref() == Some("Another Site")),
+    assert!(!example_matches.iter().any(|c| c.item_name.as_deref() == Some("Another Site")),
         "example.com should NOT match Another Site");
 
     // Test 3: another-example.com should only match Another Site
     let another_matches = filter(credentials.clone(), "https://another-example.com/signin", "E2E Test Form");
     assert_eq!(another_matches.len(), 1, "another-example.com should match exactly one credential");
-    assert_eq!(another_matches[0].service_name.as_deref(), Some("Another Site"));
+    assert_eq!(another_matches[0].item_name.as_deref(), Some("Another Site"));
 
     // Test 4: test.example.com subdomain should match Example Subdomain
     let subdomain_matches = filter(credentials, "https://test.example.com/auth", "E2E Test Form");
     assert!(subdomain_matches.len() >= 1, "test.example.com should match at least one credential");
-    assert!(subdomain_matches.iter().any(|c| c.service_name.as_deref() == Some("Example Subdomain")),
+    assert!(subdomain_matches.iter().any(|c| c.item_name.as_deref() == Some("Example Subdomain")),
         "test.example.com should match Example Subdomain");
-    assert!(!subdomain_matches.iter().any(|c| c.service_name.as_deref() == Some("Another Site")),
+    assert!(!subdomain_matches.iter().any(|c| c.item_name.as_deref() == Some("Another Site")),
         "test.example.com should NOT match Another Site");
 }
@@ -0,0 +1,377 @@
+//! Emergency-access delegated unlock.
+//!
+//! Lets a vault owner grant a trusted contact time-delayed access to their
+//! vault key without the server - or the grantee's client, before it's
+//! actually time - ever seeing the key in the clear:
+//! - [`emergency_grant`] seals the vault key to the grantee's public key
+//!   (X25519 or RSA, see [`seal`]) and attaches the grant's wait period and
+//!   creation time as metadata.
+//! - [`emergency_access_ready`] decides whether the wait period has
+//!   elapsed, or the grantor has explicitly approved/rejected the request.
+//! - [`emergency_unseal_vault_key`] lets the grantee recover the vault key
+//!   with their own private key once a grant is ready.
+//!
+//! All of this stays in the Rust core so Swift/Kotlin clients never touch
+//! raw key material - mirroring how [`crate::srp`] keeps its own crypto out
+//! of the host language.
+
+mod seal;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{VaultError, VaultResult};
+
+/// Public-key algorithm a grantee's key (and a sealed grant) is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum EmergencyKeyAlgorithm {
+    X25519,
+    Rsa,
+}
+
+/// A grantor's decision on an emergency-access request, as recorded on
+/// their account. See [`emergency_access_ready`] for how this combines with
+/// the wait period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GrantDecision {
+    /// The grantor hasn't acted - access is auto-approved once the wait
+    /// period elapses.
+    #[default]
+    Pending,
+    /// The grantor approved the request early - access is ready immediately.
+    Approved,
+    /// The grantor explicitly rejected the request - access is never ready,
+    /// regardless of how much time has passed.
+    Rejected,
+}
+
+fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+fn hex_to_bytes(hex: &str) -> VaultResult<Vec<u8>> {
+    let hex = hex.trim();
+    if hex.len() % 2 != 0 {
+        return Err(VaultError::General(format!("odd length hex string: {}", hex.len())));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map_err(|e| VaultError::General(format!("invalid hex at position {i}: {e}")))
+        })
+        .collect()
+}
+
+/// Input for [`emergency_grant`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmergencyGrantInput {
+    /// Grantee's public key: 32 raw bytes (hex) for X25519, or an SPKI DER
+    /// encoding (hex) for RSA - per `key_algorithm`.
+    pub grantee_public_key: String,
+    /// Algorithm `grantee_public_key` is in.
+    pub key_algorithm: EmergencyKeyAlgorithm,
+    /// The vault's symmetric encryption key (hex) to seal to the grantee.
+    pub vault_key: String,
+    /// Opaque identifier of the grantee account, carried through as grant
+    /// metadata so the caller can look the grantee up without re-deriving it.
+    pub grantee_id: String,
+    /// Days the grantee must wait after requesting access before it's
+    /// auto-approved - see [`emergency_access_ready`].
+    pub wait_days: u32,
+    /// Unix timestamp (seconds) this grant is created at.
+    pub created_at: u64,
+}
+
+/// Output of [`emergency_grant`].
+#[derive(Debug, Clone, Serialize)]
+pub struct EmergencyGrantOutput {
+    /// The vault key, sealed to the grantee's public key (hex) - opaque to
+    /// everyone but the holder of the matching private key.
+    pub sealed_vault_key: String,
+    pub grantee_id: String,
+    pub wait_days: u32,
+    pub created_at: u64,
+}
+
+/// Seals a vault key into an emergency-access grant for `input.grantee_id`.
+pub fn emergency_grant(input: EmergencyGrantInput) -> VaultResult<EmergencyGrantOutput> {
+    let vault_key = hex_to_bytes(&input.vault_key)?;
+    let grantee_public_key = hex_to_bytes(&input.grantee_public_key)?;
+
+    let sealed = match input.key_algorithm {
+        EmergencyKeyAlgorithm::X25519 => {
+            let key: [u8; 32] = grantee_public_key
+                .as_slice()
+                .try_into()
+                .map_err(|_| VaultError::General("X25519 public key must be 32 bytes".to_string()))?;
+            seal::seal_to_x25519(&key, &vault_key)?
+        },
+        EmergencyKeyAlgorithm::Rsa => seal::seal_to_rsa(&grantee_public_key, &vault_key)?,
+    };
+
+    Ok(EmergencyGrantOutput {
+        sealed_vault_key: bytes_to_hex(&sealed),
+        grantee_id: input.grantee_id,
+        wait_days: input.wait_days,
+        created_at: input.created_at,
+    })
+}
+
+/// JSON-in/JSON-out wrapper around [`emergency_grant`] for the UniFFI boundary.
+pub fn emergency_grant_json(input_json: &str) -> VaultResult<String> {
+    let input: EmergencyGrantInput = serde_json::from_str(input_json)?;
+    let output = emergency_grant(input)?;
+    Ok(serde_json::to_string(&output)?)
+}
+
+/// Input for [`emergency_access_ready`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmergencyAccessReadyInput {
+    /// Unix timestamp (seconds) the grant was created at.
+    pub created_at: u64,
+    /// Days the grantee must wait before access is auto-approved.
+    pub wait_days: u32,
+    /// The grantor's current decision on this request.
+    #[serde(default)]
+    pub decision: GrantDecision,
+    /// Unix timestamp (seconds) to evaluate readiness at.
+    pub current_time: u64,
+}
+
+/// Output of [`emergency_access_ready`].
+#[derive(Debug, Clone, Serialize)]
+pub struct EmergencyAccessReadyOutput {
+    /// Whether the grantee may now call [`emergency_unseal_vault_key`].
+    pub ready: bool,
+    /// Seconds remaining until the wait period elapses (0 once `ready` or
+    /// the grantor has already decided).
+    pub seconds_remaining: u64,
+}
+
+/// Seconds in a day, for converting [`EmergencyGrantInput::wait_days`] /
+/// [`EmergencyAccessReadyInput::wait_days`] into a duration.
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Decides whether an emergency-access grant is ready for the grantee to
+/// decrypt: auto-approved once `wait_days` have elapsed since `created_at`,
+/// approved immediately if the grantor explicitly approved it early, or
+/// never ready if the grantor explicitly rejected it.
+pub fn emergency_access_ready(input: EmergencyAccessReadyInput) -> EmergencyAccessReadyOutput {
+    let wait_seconds = u64::from(input.wait_days) * SECONDS_PER_DAY;
+    let elapsed = input.current_time.saturating_sub(input.created_at);
+    let seconds_remaining = wait_seconds.saturating_sub(elapsed);
+
+    let ready = match input.decision {
+        GrantDecision::Rejected => false,
+        GrantDecision::Approved => true,
+        GrantDecision::Pending => elapsed >= wait_seconds,
+    };
+
+    EmergencyAccessReadyOutput { ready, seconds_remaining: if ready { 0 } else { seconds_remaining } }
+}
+
+/// JSON-in/JSON-out wrapper around [`emergency_access_ready`] for the
+/// UniFFI boundary.
+pub fn emergency_access_ready_json(input_json: &str) -> VaultResult<String> {
+    let input: EmergencyAccessReadyInput = serde_json::from_str(input_json)?;
+    let output = emergency_access_ready(input);
+    Ok(serde_json::to_string(&output)?)
+}
+
+/// Input for [`emergency_unseal_vault_key`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmergencyUnsealInput {
+    /// The sealed vault key, as returned by [`emergency_grant`].
+    pub sealed_vault_key: String,
+    /// Algorithm `grantee_private_key` is in.
+    pub key_algorithm: EmergencyKeyAlgorithm,
+    /// Grantee's private key: 32 raw bytes (hex) for X25519, or a PKCS#8
+    /// DER encoding (hex) for RSA - per `key_algorithm`.
+    pub grantee_private_key: String,
+}
+
+/// Output of [`emergency_unseal_vault_key`].
+#[derive(Debug, Clone, Serialize)]
+pub struct EmergencyUnsealOutput {
+    /// The recovered vault symmetric key (hex).
+    pub vault_key: String,
+}
+
+/// Recovers the vault key from a ready emergency-access grant. Callers must
+/// check [`emergency_access_ready`] first - this function doesn't re-check
+/// the wait period or grantor decision itself.
+pub fn emergency_unseal_vault_key(input: EmergencyUnsealInput) -> VaultResult<EmergencyUnsealOutput> {
+    let sealed = hex_to_bytes(&input.sealed_vault_key)?;
+    let private_key = hex_to_bytes(&input.grantee_private_key)?;
+
+    let vault_key = match input.key_algorithm {
+        EmergencyKeyAlgorithm::X25519 => {
+            let key: [u8; 32] = private_key
+                .as_slice()
+                .try_into()
+                .map_err(|_| VaultError::General("X25519 private key must be 32 bytes".to_string()))?;
+            seal::unseal_from_x25519(&key, &sealed)?
+        },
+        EmergencyKeyAlgorithm::Rsa => seal::unseal_from_rsa(&private_key, &sealed)?,
+    };
+
+    Ok(EmergencyUnsealOutput { vault_key: bytes_to_hex(&vault_key) })
+}
+
+/// JSON-in/JSON-out wrapper around [`emergency_unseal_vault_key`] for the
+/// UniFFI boundary.
+pub fn emergency_unseal_vault_key_json(input_json: &str) -> VaultResult<String> {
+    let input: EmergencyUnsealInput = serde_json::from_str(input_json)?;
+    let output = emergency_unseal_vault_key(input)?;
+    Ok(serde_json::to_string(&output)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa::pkcs8::{EncodePrivateKey, EncodePublicKey};
+    use x25519_dalek::{PublicKey, StaticSecret};
+
+    #[test]
+    fn test_grant_and_unseal_x25519_round_trip() {
+        let grantee_secret_key = [0x11u8; 32];
+        let grantee_secret = StaticSecret::from(grantee_secret_key);
+        let grantee_public = PublicKey::from(&grantee_secret);
+
+        let grant = emergency_grant(EmergencyGrantInput {
+            grantee_public_key: bytes_to_hex(grantee_public.as_bytes()),
+            key_algorithm: EmergencyKeyAlgorithm::X25519,
+            vault_key: bytes_to_hex(b"this-is-the-vault-symmetric-key"),
+            grantee_id: "grantee-1".to_string(),
+            wait_days: 7,
+            created_at: 1_700_000_000,
+        })
+        .unwrap();
+
+        let unsealed = emergency_unseal_vault_key(EmergencyUnsealInput {
+            sealed_vault_key: grant.sealed_vault_key,
+            key_algorithm: EmergencyKeyAlgorithm::X25519,
+            grantee_private_key: bytes_to_hex(&grantee_secret_key),
+        })
+        .unwrap();
+
+        assert_eq!(hex_to_bytes(&unsealed.vault_key).unwrap(), b"this-is-the-vault-symmetric-key");
+    }
+
+    #[test]
+    fn test_grant_and_unseal_rsa_round_trip() {
+        let private_key = rsa::RsaPrivateKey::new(&mut rand::thread_rng(), 2048).unwrap();
+        let public_key = rsa::RsaPublicKey::from(&private_key);
+
+        let grant = emergency_grant(EmergencyGrantInput {
+            grantee_public_key: bytes_to_hex(public_key.to_public_key_der().unwrap().as_bytes()),
+            key_algorithm: EmergencyKeyAlgorithm::Rsa,
+            vault_key: bytes_to_hex(b"this-is-the-vault-symmetric-key"),
+            grantee_id: "grantee-2".to_string(),
+            wait_days: 14,
+            created_at: 1_700_000_000,
+        })
+        .unwrap();
+
+        let unsealed = emergency_unseal_vault_key(EmergencyUnsealInput {
+            sealed_vault_key: grant.sealed_vault_key,
+            key_algorithm: EmergencyKeyAlgorithm::Rsa,
+            grantee_private_key: bytes_to_hex(private_key.to_pkcs8_der().unwrap().as_bytes()),
+        })
+        .unwrap();
+
+        assert_eq!(hex_to_bytes(&unsealed.vault_key).unwrap(), b"this-is-the-vault-symmetric-key");
+    }
+
+    #[test]
+    fn test_unseal_with_wrong_grantee_fails() {
+        let grantee_secret = StaticSecret::from([0x11u8; 32]);
+        let grantee_public = PublicKey::from(&grantee_secret);
+
+        let grant = emergency_grant(EmergencyGrantInput {
+            grantee_public_key: bytes_to_hex(grantee_public.as_bytes()),
+            key_algorithm: EmergencyKeyAlgorithm::X25519,
+            vault_key: bytes_to_hex(b"this-is-the-vault-symmetric-key"),
+            grantee_id: "grantee-1".to_string(),
+            wait_days: 7,
+            created_at: 1_700_000_000,
+        })
+        .unwrap();
+
+        let result = emergency_unseal_vault_key(EmergencyUnsealInput {
+            sealed_vault_key: grant.sealed_vault_key,
+            key_algorithm: EmergencyKeyAlgorithm::X25519,
+            grantee_private_key: bytes_to_hex(&[0x22u8; 32]),
+        });
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_access_ready_pending_before_and_after_wait_period() {
+        let before = emergency_access_ready(EmergencyAccessReadyInput {
+            created_at: 1_700_000_000,
+            wait_days: 7,
+            decision: GrantDecision::Pending,
+            current_time: 1_700_000_000 + 6 * SECONDS_PER_DAY,
+        });
+        assert!(!before.ready);
+        assert_eq!(before.seconds_remaining, SECONDS_PER_DAY);
+
+        let after = emergency_access_ready(EmergencyAccessReadyInput {
+            created_at: 1_700_000_000,
+            wait_days: 7,
+            decision: GrantDecision::Pending,
+            current_time: 1_700_000_000 + 7 * SECONDS_PER_DAY,
+        });
+        assert!(after.ready);
+        assert_eq!(after.seconds_remaining, 0);
+    }
+
+    #[test]
+    fn test_access_ready_approved_short_circuits_wait_period() {
+        let output = emergency_access_ready(EmergencyAccessReadyInput {
+            created_at: 1_700_000_000,
+            wait_days: 30,
+            decision: GrantDecision::Approved,
+            current_time: 1_700_000_000 + 10,
+        });
+
+        assert!(output.ready);
+    }
+
+    #[test]
+    fn test_access_ready_rejected_never_ready() {
+        let output = emergency_access_ready(EmergencyAccessReadyInput {
+            created_at: 1_700_000_000,
+            wait_days: 7,
+            decision: GrantDecision::Rejected,
+            current_time: 1_700_000_000 + 365 * SECONDS_PER_DAY,
+        });
+
+        assert!(!output.ready);
+    }
+
+    #[test]
+    fn test_emergency_grant_json_round_trip() {
+        let grantee_secret = StaticSecret::from([0x11u8; 32]);
+        let grantee_public = PublicKey::from(&grantee_secret);
+
+        let input_json = serde_json::json!({
+            "grantee_public_key": bytes_to_hex(grantee_public.as_bytes()),
+            "key_algorithm": "X25519",
+            "vault_key": bytes_to_hex(b"this-is-the-vault-symmetric-key"),
+            "grantee_id": "grantee-1",
+            "wait_days": 7,
+            "created_at": 1_700_000_000u64,
+        })
+        .to_string();
+
+        let output_json = emergency_grant_json(&input_json).unwrap();
+        let output: serde_json::Value = serde_json::from_str(&output_json).unwrap();
+        assert_eq!(output["grantee_id"], "grantee-1");
+        assert!(output["sealed_vault_key"].is_string());
+    }
+}
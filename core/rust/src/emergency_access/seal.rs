@@ -0,0 +1,164 @@
+//! Public-key sealing of the vault key to a grantee, so the party holding a
+//! grant never has to trust the server with anything but ciphertext.
+//!
+//! X25519 uses an anonymous "sealed box" construction: a fresh ephemeral
+//! keypair is Diffie-Hellman'd against the grantee's static public key, the
+//! shared secret is expanded via HKDF-SHA256 into an AES-256-GCM key, and
+//! the ephemeral public key travels alongside the ciphertext so the
+//! grantee can redo the same ECDH with their private key. RSA instead
+//! encrypts the vault key directly under RSA-OAEP-SHA256, since it has no
+//! ephemeral-key step of its own.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use hkdf::Hkdf;
+use rand::rngs::OsRng;
+use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use rsa::{Oaep, RsaPrivateKey, RsaPublicKey};
+use sha2::Sha256;
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+
+use crate::error::{VaultError, VaultResult};
+
+const X25519_PUBLIC_KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+/// Expands an X25519 shared secret into an AES-256-GCM key, domain-separated
+/// from any other use of the same ECDH output.
+fn derive_seal_key(shared_secret: &[u8]) -> VaultResult<[u8; 32]> {
+    let hkdf = Hkdf::<Sha256>::new(None, shared_secret);
+    let mut key = [0u8; 32];
+    hkdf.expand(b"aliasvault-emergency-access-seal", &mut key)
+        .map_err(|e| VaultError::General(format!("HKDF expansion failed: {e}")))?;
+    Ok(key)
+}
+
+/// Seals `plaintext` (the vault key) to `grantee_public_key` using an
+/// anonymous X25519 sealed box. Returns `ephemeral_public || nonce ||
+/// ciphertext`.
+pub fn seal_to_x25519(grantee_public_key: &[u8; X25519_PUBLIC_KEY_LEN], plaintext: &[u8]) -> VaultResult<Vec<u8>> {
+    let grantee_public = PublicKey::from(*grantee_public_key);
+    let ephemeral_secret = EphemeralSecret::random_from_rng(OsRng);
+    let ephemeral_public = PublicKey::from(&ephemeral_secret);
+    let shared_secret = ephemeral_secret.diffie_hellman(&grantee_public);
+
+    let key = derive_seal_key(shared_secret.as_bytes())?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| VaultError::General(format!("invalid seal key: {e}")))?;
+
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut nonce_bytes);
+    let ciphertext =
+        cipher.encrypt(Nonce::from_slice(&nonce_bytes), plaintext).map_err(|e| VaultError::General(format!("seal failed: {e}")))?;
+
+    let mut sealed = Vec::with_capacity(X25519_PUBLIC_KEY_LEN + NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(ephemeral_public.as_bytes());
+    sealed.extend_from_slice(&nonce_bytes);
+    sealed.extend(ciphertext);
+    Ok(sealed)
+}
+
+/// Reverses [`seal_to_x25519`]: recovers the ephemeral public key and nonce
+/// from `sealed`, redoes the ECDH with `grantee_secret_key`, and decrypts.
+pub fn unseal_from_x25519(grantee_secret_key: &[u8; X25519_PUBLIC_KEY_LEN], sealed: &[u8]) -> VaultResult<Vec<u8>> {
+    if sealed.len() < X25519_PUBLIC_KEY_LEN + NONCE_LEN {
+        return Err(VaultError::General("sealed vault key is too short".to_string()));
+    }
+    let (ephemeral_public_bytes, rest) = sealed.split_at(X25519_PUBLIC_KEY_LEN);
+    let (nonce_bytes, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let ephemeral_public_bytes: [u8; X25519_PUBLIC_KEY_LEN] =
+        ephemeral_public_bytes.try_into().expect("slice length checked above");
+    let ephemeral_public = PublicKey::from(ephemeral_public_bytes);
+    let grantee_secret = StaticSecret::from(*grantee_secret_key);
+    let shared_secret = grantee_secret.diffie_hellman(&ephemeral_public);
+
+    let key = derive_seal_key(shared_secret.as_bytes())?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| VaultError::General(format!("invalid seal key: {e}")))?;
+
+    cipher
+        .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+        .map_err(|_| VaultError::General("failed to unseal vault key - wrong key or corrupted blob".to_string()))
+}
+
+/// Seals `plaintext` (the vault key) to `grantee_public_key_der` (an SPKI
+/// DER-encoded RSA public key) via RSA-OAEP-SHA256.
+pub fn seal_to_rsa(grantee_public_key_der: &[u8], plaintext: &[u8]) -> VaultResult<Vec<u8>> {
+    let public_key = RsaPublicKey::from_public_key_der(grantee_public_key_der)
+        .map_err(|e| VaultError::General(format!("invalid RSA public key: {e}")))?;
+
+    public_key
+        .encrypt(&mut rand::thread_rng(), Oaep::new::<Sha256>(), plaintext)
+        .map_err(|e| VaultError::General(format!("RSA seal failed: {e}")))
+}
+
+/// Reverses [`seal_to_rsa`] using `grantee_private_key_der` (a PKCS#8
+/// DER-encoded RSA private key).
+pub fn unseal_from_rsa(grantee_private_key_der: &[u8], sealed: &[u8]) -> VaultResult<Vec<u8>> {
+    let private_key = RsaPrivateKey::from_pkcs8_der(grantee_private_key_der)
+        .map_err(|e| VaultError::General(format!("invalid RSA private key: {e}")))?;
+
+    private_key
+        .decrypt(Oaep::new::<Sha256>(), sealed)
+        .map_err(|_| VaultError::General("failed to unseal vault key - wrong key or corrupted blob".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa::pkcs8::{EncodePrivateKey, EncodePublicKey};
+
+    #[test]
+    fn test_x25519_seal_unseal_round_trip() {
+        let grantee_secret_key = [0x11u8; 32];
+        let grantee_secret = StaticSecret::from(grantee_secret_key);
+        let grantee_public = PublicKey::from(&grantee_secret);
+        let vault_key = b"this-is-the-vault-symmetric-key";
+
+        let sealed = seal_to_x25519(grantee_public.as_bytes(), vault_key).unwrap();
+        let unsealed = unseal_from_x25519(&grantee_secret_key, &sealed).unwrap();
+
+        assert_eq!(unsealed, vault_key);
+    }
+
+    #[test]
+    fn test_x25519_unseal_with_wrong_key_fails() {
+        let grantee_secret = StaticSecret::from([0x11u8; 32]);
+        let grantee_public = PublicKey::from(&grantee_secret);
+        let vault_key = b"this-is-the-vault-symmetric-key";
+
+        let sealed = seal_to_x25519(grantee_public.as_bytes(), vault_key).unwrap();
+
+        assert!(unseal_from_x25519(&[0x22u8; 32], &sealed).is_err());
+    }
+
+    #[test]
+    fn test_x25519_seal_is_nondeterministic() {
+        let grantee_secret = StaticSecret::from([0x11u8; 32]);
+        let grantee_public = PublicKey::from(&grantee_secret);
+        let vault_key = b"this-is-the-vault-symmetric-key";
+
+        let sealed1 = seal_to_x25519(grantee_public.as_bytes(), vault_key).unwrap();
+        let sealed2 = seal_to_x25519(grantee_public.as_bytes(), vault_key).unwrap();
+
+        assert_ne!(sealed1, sealed2);
+    }
+
+    #[test]
+    fn test_rsa_seal_unseal_round_trip() {
+        let private_key = RsaPrivateKey::new(&mut rand::thread_rng(), 2048).unwrap();
+        let public_key = RsaPublicKey::from(&private_key);
+        let public_der = public_key.to_public_key_der().unwrap();
+        let private_der = private_key.to_pkcs8_der().unwrap();
+        let vault_key = b"this-is-the-vault-symmetric-key";
+
+        let sealed = seal_to_rsa(public_der.as_bytes(), vault_key).unwrap();
+        let unsealed = unseal_from_rsa(private_der.as_bytes(), &sealed).unwrap();
+
+        assert_eq!(unsealed, vault_key);
+    }
+
+    #[test]
+    fn test_rsa_seal_rejects_invalid_public_key() {
+        assert!(seal_to_rsa(b"not-a-der-key", b"vault-key").is_err());
+    }
+}
@@ -1,8 +1,12 @@
 //! AliasVault Core Library
 //!
 //! Cross-platform core functionality for AliasVault, including:
-//! - **merge**: Vault merge using Last-Write-Wins (LWW) strategy
+//! - **vault_merge**: Vault merge using Last-Write-Wins (LWW) strategy
+//! - **vault_pruner**: Automatic cleanup of expired trash items
 //! - **credential_matcher**: Cross-platform credential filtering for autofill
+//! - **srp**: Secure Remote Password protocol (registration, login, rotation)
+//! - **totp**: RFC 6238 TOTP code generation
+//! - **emergency_access**: Time-delayed delegated vault-key grants
 //!
 //! This library accepts data as JSON and returns results as JSON.
 //! Each platform (browser, iOS, Android, .NET) handles its own I/O
@@ -21,19 +25,39 @@
 //! ```
 
 pub mod error;
-pub mod merge;
+pub mod vault_merge;
+pub mod vault_pruner;
 pub mod credential_matcher;
+pub mod emergency_access;
+pub mod srp;
+pub mod totp;
+
+// C FFI exports for .NET P/Invoke.
+pub mod ffi;
 
 pub use error::VaultError;
-pub use merge::{
+pub use vault_merge::{
     merge_vaults, MergeInput, MergeOutput, MergeStats, SqlStatement, TableData,
     SYNCABLE_TABLE_NAMES,
 };
 pub use credential_matcher::{
-    filter_credentials, extract_domain, extract_root_domain,
+    filter_credentials, extract_domain, extract_root_domain, parse_query,
     AutofillMatchingMode, CredentialMatcherInput, CredentialMatcherOutput,
+    MatchReason, QueryKind, ScoredMatch, PRIORITY_DOMAIN_SUPPRESSED, PRIORITY_EXACT_ID_MATCH,
+};
+pub use totp::{generate_totp, TotpAlgorithm, TotpInput, TotpOutput};
+pub use emergency_access::{
+    emergency_access_ready, emergency_grant, emergency_unseal_vault_key, EmergencyAccessReadyInput,
+    EmergencyAccessReadyOutput, EmergencyGrantInput, EmergencyGrantOutput, EmergencyKeyAlgorithm,
+    EmergencyUnsealInput, EmergencyUnsealOutput, GrantDecision,
 };
 
+/// The version of the aliasvault-core library, as declared in `Cargo.toml`.
+/// Surfaced to client platforms for "about" screens and support diagnostics.
+pub fn get_core_version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
 // WASM bindings
 #[cfg(feature = "wasm")]
 pub mod wasm;
@@ -41,6 +65,10 @@ pub mod wasm;
 #[cfg(feature = "wasm")]
 pub use wasm::*;
 
+// UniFFI bindings for Swift and Kotlin
+#[cfg(feature = "uniffi")]
+pub mod uniffi_api;
+
 // UniFFI scaffolding
 #[cfg(feature = "uniffi")]
 uniffi::setup_scaffolding!();
@@ -0,0 +1,258 @@
+//! Stateful handshake objects wrapping the free `srp_*` functions.
+//!
+//! The free functions require the caller to manually carry `client_secret`,
+//! `private_key`, `server_public`, `salt`, and `identity` between calls -
+//! easy to misuse and easy to pass the wrong argument. [`SrpClientHandshake`]
+//! and [`SrpServerHandshake`] instead own that intermediate state across the
+//! round trip, so a caller only ever threads the one or two values that
+//! actually cross the wire (`A`/`B`, then `M1`/`M2`).
+//!
+//! Both structs are thin wrappers over the existing free functions, fixed to
+//! [`super::DefaultHash`] since `uniffi::Object` can't be implemented for a
+//! generic type; callers who need a different digest should use the free
+//! functions directly.
+
+use std::sync::Mutex;
+
+use super::{
+    srp_derive_session, srp_derive_session_server, srp_generate_ephemeral,
+    srp_generate_ephemeral_server, srp_verify_session, DefaultHash, SrpError, SrpGroup,
+    SrpKeyDerivation, SrpSession,
+};
+
+/// Client side of an SRP handshake: created with the account's identity,
+/// salt, and private key; produces `A` for the server; consumes `B` to
+/// yield the session proof (`M1`) and key (`K`); then verifies the server's
+/// proof (`M2`) without the caller re-passing `A` or `K`.
+#[cfg_attr(feature = "uniffi", derive(uniffi::Object))]
+pub struct SrpClientHandshake {
+    group: SrpGroup,
+    mode: SrpKeyDerivation,
+    identity: String,
+    salt: String,
+    private_key: String,
+    client_secret: String,
+    client_public: String,
+    session: Mutex<Option<SrpSession>>,
+}
+
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+impl SrpClientHandshake {
+    /// Start a handshake: generates the client ephemeral key pair (A, a)
+    /// for `group` and remembers `identity`/`salt`/`private_key` for the
+    /// later session derivation step. `mode` must match the server's for
+    /// this session, or the proofs won't agree.
+    #[cfg_attr(feature = "uniffi", uniffi::constructor)]
+    #[must_use]
+    pub fn new(
+        identity: String,
+        salt: String,
+        private_key: String,
+        group: SrpGroup,
+        mode: SrpKeyDerivation,
+    ) -> Self {
+        let ephemeral = srp_generate_ephemeral::<DefaultHash>(group);
+        Self {
+            group,
+            mode,
+            identity,
+            salt,
+            private_key,
+            client_secret: ephemeral.secret.clone(),
+            client_public: ephemeral.public.clone(),
+            session: Mutex::new(None),
+        }
+    }
+
+    /// The client's public ephemeral (A), to send to the server.
+    #[must_use]
+    pub fn client_public_ephemeral(&self) -> String {
+        self.client_public.clone()
+    }
+
+    /// Consume the server's public ephemeral (B) and derive the session
+    /// proof (M1) and key (K). The session is remembered internally so
+    /// `verify_server_proof` doesn't need it passed again.
+    pub fn derive_session(&self, server_public: String) -> Result<SrpSession, SrpError> {
+        let session = srp_derive_session::<DefaultHash>(
+            &self.client_secret,
+            &server_public,
+            &self.salt,
+            &self.identity,
+            &self.private_key,
+            self.group,
+            self.mode,
+        )?;
+
+        *self.session.lock().unwrap() = Some(session.clone());
+        Ok(session)
+    }
+
+    /// Verify the server's proof (M2) against the session derived by
+    /// `derive_session`.
+    ///
+    /// # Errors
+    /// Returns `SrpError::InvalidParameter` if called before `derive_session`.
+    pub fn verify_server_proof(&self, server_proof: String) -> Result<bool, SrpError> {
+        let session = self.session.lock().unwrap();
+        let session = session.as_ref().ok_or_else(|| {
+            SrpError::InvalidParameter("derive_session must be called before verify_server_proof".to_string())
+        })?;
+
+        srp_verify_session::<DefaultHash>(
+            &self.client_public,
+            &session.proof,
+            &session.key,
+            &server_proof,
+        )
+    }
+}
+
+/// Server side of an SRP handshake: created with the account's verifier;
+/// produces `B` for the client; verifies the client's proof (`M1`) and, if
+/// valid, emits the server's proof (`M2`) and the shared session key.
+#[cfg_attr(feature = "uniffi", derive(uniffi::Object))]
+pub struct SrpServerHandshake {
+    group: SrpGroup,
+    mode: SrpKeyDerivation,
+    verifier: String,
+    server_secret: String,
+    server_public: String,
+}
+
+#[cfg_attr(feature = "uniffi", uniffi::export)]
+impl SrpServerHandshake {
+    /// Start a handshake: generates the server ephemeral key pair (B, b)
+    /// for `verifier`/`group`. `mode` must match the client's for this
+    /// session, or the proofs won't agree.
+    #[cfg_attr(feature = "uniffi", uniffi::constructor)]
+    pub fn new(verifier: String, group: SrpGroup, mode: SrpKeyDerivation) -> Result<Self, SrpError> {
+        let ephemeral = srp_generate_ephemeral_server::<DefaultHash>(&verifier, group)?;
+        Ok(Self {
+            group,
+            mode,
+            verifier,
+            server_secret: ephemeral.secret.clone(),
+            server_public: ephemeral.public.clone(),
+        })
+    }
+
+    /// The server's public ephemeral (B), to send to the client.
+    #[must_use]
+    pub fn server_public_ephemeral(&self) -> String {
+        self.server_public.clone()
+    }
+
+    /// Verify the client's public ephemeral (A) and proof (M1); on success,
+    /// derive the session key (K) and server proof (M2).
+    ///
+    /// # Returns
+    /// `Some(session)` if the client proof is valid, `None` otherwise.
+    pub fn verify_client_proof(
+        &self,
+        client_public: String,
+        salt: String,
+        identity: String,
+        client_proof: String,
+    ) -> Result<Option<SrpSession>, SrpError> {
+        srp_derive_session_server::<DefaultHash>(
+            &self.server_secret,
+            &client_public,
+            &salt,
+            &identity,
+            &self.verifier,
+            &client_proof,
+            self.group,
+            self.mode,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::srp::{srp_derive_private_key, srp_derive_verifier, srp_generate_salt};
+
+    #[test]
+    fn test_handshake_full_flow() {
+        // Registration
+        let salt = srp_generate_salt();
+        let identity = "testuser@example.com".to_string();
+        let password_hash = "0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF";
+
+        let private_key = srp_derive_private_key::<DefaultHash>(&salt, &identity, password_hash).unwrap();
+        let verifier = srp_derive_verifier(&private_key, SrpGroup::G2048).unwrap();
+
+        // Login
+        let client = SrpClientHandshake::new(
+            identity.clone(),
+            salt.clone(),
+            private_key,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
+        );
+        let server =
+            SrpServerHandshake::new(verifier, SrpGroup::G2048, SrpKeyDerivation::Aliasvault).unwrap();
+
+        let client_public = client.client_public_ephemeral();
+        let server_public = server.server_public_ephemeral();
+
+        let client_session = client.derive_session(server_public).unwrap();
+
+        let server_session = server
+            .verify_client_proof(client_public, salt, identity, client_session.proof.clone())
+            .unwrap()
+            .expect("valid client proof should produce a session");
+
+        assert_eq!(client_session.key, server_session.key);
+        assert!(client.verify_server_proof(server_session.proof).unwrap());
+    }
+
+    #[test]
+    fn test_verify_server_proof_before_derive_session_errors() {
+        let client = SrpClientHandshake::new(
+            "testuser".to_string(),
+            srp_generate_salt(),
+            "AABBCCDD".to_string(),
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
+        );
+
+        assert!(client.verify_server_proof("DEADBEEF".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_handshake_rejects_wrong_password() {
+        let salt = srp_generate_salt();
+        let identity = "testuser".to_string();
+        let correct_password_hash = "CORRECT_PASSWORD_HASH_0123456789";
+        let wrong_password_hash = "WRONG_PASSWORD_HASH_0123456789AB";
+
+        let correct_private_key =
+            srp_derive_private_key::<DefaultHash>(&salt, &identity, correct_password_hash).unwrap();
+        let verifier = srp_derive_verifier(&correct_private_key, SrpGroup::G2048).unwrap();
+
+        let wrong_private_key =
+            srp_derive_private_key::<DefaultHash>(&salt, &identity, wrong_password_hash).unwrap();
+
+        let client = SrpClientHandshake::new(
+            identity.clone(),
+            salt.clone(),
+            wrong_private_key,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
+        );
+        let server =
+            SrpServerHandshake::new(verifier, SrpGroup::G2048, SrpKeyDerivation::Aliasvault).unwrap();
+
+        let client_public = client.client_public_ephemeral();
+        let server_public = server.server_public_ephemeral();
+        let client_session = client.derive_session(server_public).unwrap();
+
+        let server_session = server
+            .verify_client_proof(client_public, salt, identity, client_session.proof)
+            .unwrap();
+
+        assert!(server_session.is_none());
+    }
+}
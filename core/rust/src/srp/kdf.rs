@@ -0,0 +1,310 @@
+//! Pluggable password key-derivation functions with PHC-encoded parameters.
+//!
+//! A stored credential records which algorithm (and which parameters)
+//! produced its password hash as a PHC string
+//! (`$<id>$<param>=<value>,...$<salt>$<hash>`), so the application can
+//! support multiple KDFs side by side and detect an outdated configuration
+//! to re-hash on next login, without breaking already-stored Argon2id
+//! credentials.
+
+use argon2::{Algorithm as Argon2Algorithm, Argon2, Params as Argon2Params, Version as Argon2Version};
+use pbkdf2::pbkdf2_hmac;
+use scrypt::{scrypt, Params as ScryptParams};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+
+use super::SrpError;
+
+/// Output length, in bytes, of every KDF variant here. Matches the existing
+/// Argon2id default so stored credentials keep the same hash size.
+const OUTPUT_LEN: usize = 32;
+
+/// Which key-derivation function produced (or should produce) a stored
+/// password hash, together with its parameters. Bundling the parameters into
+/// the algorithm they belong to (rather than a separate params struct) makes
+/// an invalid algorithm/parameter pairing unrepresentable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+pub enum KdfAlgorithm {
+    /// Argon2id with memory cost (KiB), iterations, and parallelism.
+    Argon2id { m_cost: u32, t_cost: u32, p_cost: u32 },
+    /// Scrypt with CPU/memory cost as log2(N), block size `r`, and parallelism `p`.
+    Scrypt { log_n: u8, r: u32, p: u32 },
+    /// PBKDF2-HMAC-SHA256 with an iteration count.
+    Pbkdf2Sha256 { iterations: u32 },
+}
+
+impl Default for KdfAlgorithm {
+    /// Matches AliasVault's pre-existing `argon2_hash_password` defaults, so
+    /// upgrading an account's KDF is opt-in rather than a breaking change.
+    fn default() -> Self {
+        KdfAlgorithm::Argon2id {
+            m_cost: 19456,
+            t_cost: 2,
+            p_cost: 1,
+        }
+    }
+}
+
+impl KdfAlgorithm {
+    /// The PHC algorithm identifier for this variant.
+    fn phc_id(self) -> &'static str {
+        match self {
+            KdfAlgorithm::Argon2id { .. } => "argon2id",
+            KdfAlgorithm::Scrypt { .. } => "scrypt",
+            KdfAlgorithm::Pbkdf2Sha256 { .. } => "pbkdf2-sha256",
+        }
+    }
+
+    /// The PHC parameter segment (everything between the algorithm id and
+    /// the salt), e.g. `v=19$m=19456,t=2,p=1` for Argon2id.
+    fn phc_params(self) -> String {
+        match self {
+            KdfAlgorithm::Argon2id { m_cost, t_cost, p_cost } => {
+                format!("v=19$m={},t={},p={}", m_cost, t_cost, p_cost)
+            }
+            KdfAlgorithm::Scrypt { log_n, r, p } => format!("ln={},r={},p={}", log_n, r, p),
+            KdfAlgorithm::Pbkdf2Sha256 { iterations } => format!("i={}", iterations),
+        }
+    }
+
+    /// Derives `OUTPUT_LEN` raw key bytes for `password`/`salt` under this algorithm.
+    pub(crate) fn derive(self, password: &[u8], salt: &[u8]) -> Result<[u8; OUTPUT_LEN], SrpError> {
+        let mut output = [0u8; OUTPUT_LEN];
+        match self {
+            KdfAlgorithm::Argon2id { m_cost, t_cost, p_cost } => {
+                let params = Argon2Params::new(m_cost, t_cost, p_cost, Some(OUTPUT_LEN))
+                    .map_err(|e| SrpError::InvalidParameter(format!("invalid Argon2 params: {}", e)))?;
+                let argon2 = Argon2::new(Argon2Algorithm::Argon2id, Argon2Version::V0x13, params);
+                argon2
+                    .hash_password_into(password, salt, &mut output)
+                    .map_err(|e| SrpError::InvalidParameter(format!("Argon2 hash failed: {}", e)))?;
+            }
+            KdfAlgorithm::Scrypt { log_n, r, p } => {
+                let params = ScryptParams::new(log_n, r, p, OUTPUT_LEN)
+                    .map_err(|e| SrpError::InvalidParameter(format!("invalid scrypt params: {}", e)))?;
+                scrypt(password, salt, &params, &mut output)
+                    .map_err(|e| SrpError::InvalidParameter(format!("scrypt hash failed: {}", e)))?;
+            }
+            KdfAlgorithm::Pbkdf2Sha256 { iterations } => {
+                pbkdf2_hmac::<Sha256>(password, salt, iterations, &mut output);
+            }
+        }
+        Ok(output)
+    }
+
+    /// Parses the PHC parameter segment (without the leading `$<id>$`) back
+    /// into a [`KdfAlgorithm`] variant matching `id`.
+    fn parse(id: &str, params: &str) -> Result<Self, SrpError> {
+        let field = |key: &str| -> Result<&str, SrpError> {
+            params
+                .split(['$', ','])
+                .find_map(|kv| kv.strip_prefix(key))
+                .ok_or_else(|| SrpError::InvalidParameter(format!("missing '{}' in KDF params", key)))
+        };
+        let parse_u32 = |key: &str| -> Result<u32, SrpError> {
+            field(key)?
+                .parse()
+                .map_err(|_| SrpError::InvalidParameter(format!("invalid '{}' in KDF params", key)))
+        };
+
+        match id {
+            "argon2id" => Ok(KdfAlgorithm::Argon2id {
+                m_cost: parse_u32("m=")?,
+                t_cost: parse_u32("t=")?,
+                p_cost: parse_u32("p=")?,
+            }),
+            "scrypt" => Ok(KdfAlgorithm::Scrypt {
+                log_n: parse_u32("ln=")? as u8,
+                r: parse_u32("r=")?,
+                p: parse_u32("p=")?,
+            }),
+            "pbkdf2-sha256" => Ok(KdfAlgorithm::Pbkdf2Sha256 {
+                iterations: parse_u32("i=")?,
+            }),
+            other => Err(SrpError::InvalidParameter(format!("unknown KDF algorithm: {}", other))),
+        }
+    }
+}
+
+/// Hash `password` with `salt` under `algorithm`, returning a PHC-format string
+/// (e.g. `$argon2id$v=19$m=19456,t=2,p=1$<salt>$<hash>`) that records both the
+/// algorithm's parameters and the salt, so [`kdf_verify_password`] can later
+/// re-derive the same hash without the caller tracking anything separately.
+///
+/// # Arguments
+/// * `algorithm` - KDF and parameters to hash with
+/// * `password` - The password to hash
+/// * `salt` - Salt as a string (will be UTF-8 encoded)
+///
+/// # Returns
+/// PHC-format string suitable for storage
+pub fn kdf_hash_password(algorithm: KdfAlgorithm, password: &str, salt: &str) -> Result<String, SrpError> {
+    let hash = algorithm.derive(password.as_bytes(), salt.as_bytes())?;
+    Ok(format!(
+        "${}${}${}${}",
+        algorithm.phc_id(),
+        algorithm.phc_params(),
+        base64_encode(salt.as_bytes()),
+        base64_encode(&hash),
+    ))
+}
+
+/// Verify `password` against a previously-stored `phc_string` from
+/// [`kdf_hash_password`], re-deriving the hash with the same algorithm,
+/// parameters, and salt recorded in the string and comparing in constant time.
+///
+/// # Returns
+/// `Ok(true)` if `password` matches, `Ok(false)` if it doesn't, `Err` if
+/// `phc_string` is malformed or names an unsupported algorithm.
+pub fn kdf_verify_password(password: &str, phc_string: &str) -> Result<bool, SrpError> {
+    let mut parts = phc_string.splitn(2, '$').skip(1);
+    let rest = parts
+        .next()
+        .ok_or_else(|| SrpError::InvalidParameter("empty PHC string".to_string()))?;
+
+    let (id, rest) = rest
+        .split_once('$')
+        .ok_or_else(|| SrpError::InvalidParameter("malformed PHC string".to_string()))?;
+
+    // Everything between the id and the final two `$`-separated fields
+    // (salt, hash) is the parameter segment.
+    let fields: Vec<&str> = rest.rsplitn(3, '$').collect();
+    if fields.len() != 3 {
+        return Err(SrpError::InvalidParameter("malformed PHC string".to_string()));
+    }
+    let (hash_b64, salt_b64, params) = (fields[0], fields[1], fields[2]);
+
+    let algorithm = KdfAlgorithm::parse(id, params)?;
+    let salt_bytes = base64_decode(salt_b64)?;
+    let expected_hash = base64_decode(hash_b64)?;
+
+    let salt = String::from_utf8(salt_bytes)
+        .map_err(|e| SrpError::InvalidParameter(format!("salt is not valid UTF-8: {}", e)))?;
+    let actual_hash = algorithm.derive(password.as_bytes(), salt.as_bytes())?;
+
+    Ok(actual_hash.ct_eq(&expected_hash[..]).unwrap_u8() == 1)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Base64 (unpadded, standard alphabet - PHC strings don't use hex or padding)
+// ═══════════════════════════════════════════════════════════════════════════════
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity((bytes.len() * 4).div_ceil(3));
+    for chunk in bytes.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = chunk.get(1).copied();
+        let b2 = chunk.get(2).copied();
+
+        out.push(BASE64_ALPHABET[(b0 >> 2) as usize] as char);
+        out.push(BASE64_ALPHABET[((b0 & 0x03) << 4 | b1.unwrap_or(0) >> 4) as usize] as char);
+        if let Some(b1) = b1 {
+            out.push(BASE64_ALPHABET[((b1 & 0x0F) << 2 | b2.unwrap_or(0) >> 6) as usize] as char);
+        }
+        if let Some(b2) = b2 {
+            out.push(BASE64_ALPHABET[(b2 & 0x3F) as usize] as char);
+        }
+    }
+    out
+}
+
+fn base64_decode(encoded: &str) -> Result<Vec<u8>, SrpError> {
+    let value_of = |c: u8| -> Result<u8, SrpError> {
+        BASE64_ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .map(|p| p as u8)
+            .ok_or_else(|| SrpError::InvalidParameter(format!("invalid base64 character: {}", c as char)))
+    };
+
+    let chars: Vec<u8> = encoded.bytes().collect();
+    let mut out = Vec::with_capacity(chars.len() * 3 / 4);
+
+    for chunk in chars.chunks(4) {
+        if chunk.len() < 2 {
+            return Err(SrpError::InvalidParameter("base64 string has a dangling trailing character".to_string()));
+        }
+
+        let v0 = value_of(chunk[0])?;
+        let v1 = value_of(chunk[1])?;
+        out.push((v0 << 2) | (v1 >> 4));
+
+        if let Some(&c2) = chunk.get(2) {
+            let v2 = value_of(c2)?;
+            out.push((v1 << 4) | (v2 >> 2));
+
+            if let Some(&c3) = chunk.get(3) {
+                let v3 = value_of(c3)?;
+                out.push((v2 << 6) | v3);
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_round_trip() {
+        for original in [&b""[..], b"f", b"fo", b"foo", b"foob", b"fooba", b"foobar"] {
+            let encoded = base64_encode(original);
+            let decoded = base64_decode(&encoded).unwrap();
+            assert_eq!(decoded, original);
+        }
+    }
+
+    #[test]
+    fn test_argon2id_hash_and_verify() {
+        let algorithm = KdfAlgorithm::default();
+        let phc = kdf_hash_password(algorithm, "correct horse battery staple", "somesalt123").unwrap();
+
+        assert!(phc.starts_with("$argon2id$v=19$m=19456,t=2,p=1$"));
+        assert!(kdf_verify_password("correct horse battery staple", &phc).unwrap());
+        assert!(!kdf_verify_password("wrong password", &phc).unwrap());
+    }
+
+    #[test]
+    fn test_scrypt_hash_and_verify() {
+        let algorithm = KdfAlgorithm::Scrypt { log_n: 14, r: 8, p: 1 };
+        let phc = kdf_hash_password(algorithm, "hunter2", "scryptsalt").unwrap();
+
+        assert!(phc.starts_with("$scrypt$ln=14,r=8,p=1$"));
+        assert!(kdf_verify_password("hunter2", &phc).unwrap());
+        assert!(!kdf_verify_password("hunter3", &phc).unwrap());
+    }
+
+    #[test]
+    fn test_pbkdf2_hash_and_verify() {
+        let algorithm = KdfAlgorithm::Pbkdf2Sha256 { iterations: 10_000 };
+        let phc = kdf_hash_password(algorithm, "letmein", "pbkdf2salt").unwrap();
+
+        assert!(phc.starts_with("$pbkdf2-sha256$i=10000$"));
+        assert!(kdf_verify_password("letmein", &phc).unwrap());
+        assert!(!kdf_verify_password("letmein2", &phc).unwrap());
+    }
+
+    #[test]
+    fn test_verify_rejects_malformed_phc_string() {
+        assert!(kdf_verify_password("password", "not-a-phc-string").is_err());
+        assert!(kdf_verify_password("password", "$unknownalgo$p=1$c2FsdA$aGFzaA").is_err());
+    }
+
+    #[test]
+    fn test_base64_decode_rejects_dangling_trailing_character() {
+        // 5 chars = a 4n+1 length, which can't come from any valid encoding.
+        assert!(base64_decode("abcde").is_err());
+    }
+
+    #[test]
+    fn test_verify_rejects_phc_string_with_dangling_base64_hash() {
+        // Well-formed except the hash segment has a dangling trailing
+        // character - must return Err, not panic.
+        assert!(kdf_verify_password("password", "$argon2id$v=19$m=19456,t=2,p=1$c2FsdA$aGFzaA9").is_err());
+    }
+}
@@ -0,0 +1,97 @@
+//! AEAD wrapping of the vault's symmetric encryption key under a
+//! password-derived key.
+//!
+//! Used by [`super::rotation::srp_rotate_credentials`] to move the vault
+//! key's wrapping from the old password-derived key to the new one in the
+//! same call that re-derives the SRP salt/verifier.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+
+use super::{bytes_to_hex, generate_random_bytes, hex_to_bytes, SrpError};
+
+const NONCE_LEN: usize = 12;
+
+/// Wraps `plaintext` (the raw vault key) under `key` (a 32-byte
+/// password-derived key), returning `nonce || ciphertext` as uppercase hex.
+pub fn wrap_key(key: &[u8], plaintext: &[u8]) -> Result<String, SrpError> {
+    let cipher =
+        Aes256Gcm::new_from_slice(key).map_err(|e| SrpError::InvalidParameter(format!("invalid wrapping key: {e}")))?;
+    let nonce_bytes = generate_random_bytes(NONCE_LEN);
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let ciphertext =
+        cipher.encrypt(nonce, plaintext).map_err(|e| SrpError::InvalidParameter(format!("key wrap failed: {e}")))?;
+
+    let mut wrapped = nonce_bytes;
+    wrapped.extend(ciphertext);
+    Ok(bytes_to_hex(&wrapped))
+}
+
+/// Reverses [`wrap_key`]: splits `wrapped` into its nonce and ciphertext and
+/// decrypts with `key`.
+pub fn unwrap_key(key: &[u8], wrapped: &str) -> Result<Vec<u8>, SrpError> {
+    let bytes = hex_to_bytes(wrapped)?;
+    if bytes.len() <= NONCE_LEN {
+        return Err(SrpError::InvalidParameter("wrapped key is too short".to_string()));
+    }
+    let (nonce_bytes, ciphertext) = bytes.split_at(NONCE_LEN);
+
+    let cipher =
+        Aes256Gcm::new_from_slice(key).map_err(|e| SrpError::InvalidParameter(format!("invalid wrapping key: {e}")))?;
+    let nonce = Nonce::from_slice(nonce_bytes);
+
+    cipher.decrypt(nonce, ciphertext).map_err(|_| {
+        SrpError::AuthenticationFailed("failed to unwrap vault key - wrong key or corrupted blob".to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_unwrap_round_trip() {
+        let key = [0x11u8; 32];
+        let vault_key = b"super-secret-vault-key-bytes!!!";
+
+        let wrapped = wrap_key(&key, vault_key).unwrap();
+        let unwrapped = unwrap_key(&key, &wrapped).unwrap();
+
+        assert_eq!(unwrapped, vault_key);
+    }
+
+    #[test]
+    fn test_unwrap_with_wrong_key_fails() {
+        let key = [0x11u8; 32];
+        let other_key = [0x22u8; 32];
+        let vault_key = b"super-secret-vault-key-bytes!!!";
+
+        let wrapped = wrap_key(&key, vault_key).unwrap();
+
+        assert!(unwrap_key(&other_key, &wrapped).is_err());
+    }
+
+    #[test]
+    fn test_unwrap_rejects_tampered_ciphertext() {
+        let key = [0x11u8; 32];
+        let vault_key = b"super-secret-vault-key-bytes!!!";
+
+        let mut wrapped_bytes = hex_to_bytes(&wrap_key(&key, vault_key).unwrap()).unwrap();
+        let last = wrapped_bytes.len() - 1;
+        wrapped_bytes[last] ^= 0xff;
+
+        assert!(unwrap_key(&key, &bytes_to_hex(&wrapped_bytes)).is_err());
+    }
+
+    #[test]
+    fn test_wrap_produces_distinct_ciphertext_each_call() {
+        let key = [0x11u8; 32];
+        let vault_key = b"super-secret-vault-key-bytes!!!";
+
+        let wrapped1 = wrap_key(&key, vault_key).unwrap();
+        let wrapped2 = wrap_key(&key, vault_key).unwrap();
+
+        assert_ne!(wrapped1, wrapped2);
+    }
+}
@@ -1,14 +1,21 @@
 //! SRP (Secure Remote Password) protocol implementation.
 //!
 //! # Protocol Parameters
-//! - Group: RFC 5054 2048-bit
-//! - Hash: SHA-256
-//! - Multiplier k: Computed as `k = H(N, PAD(g))`
-//! - All values: Uppercase hex strings
+//! - Group: RFC 5054, selectable via [`SrpGroup`] (defaults to 2048-bit)
+//! - Hash: generic over any [`Digest`] (defaults to [`DefaultHash`] = SHA-256),
+//!   matching the upstream `srp` crate's `SrpClient<D>`/`SrpServer<D>`
+//! - Multiplier k: Computed as `k = H(N, PAD(g))`, padded to the selected group's byte length
+//! - All values: Uppercase hex strings; hash-derived values (`x`, `u`, `k`, `M1`, `M2`)
+//!   are `D::output_size()` bytes long rather than a hardcoded 32
+//! - Session key K: `H(S)` by default, or the RFC 2945/5054 interleaved hash (twice
+//!   `D::output_size()` bytes) when [`SrpKeyDerivation::Rfc5054Interleaved`] is selected -
+//!   see [`SrpKeyDerivation`]
 //!
 //! # Client Operations
 //! - `srp_generate_salt()` - Generate a 32-byte cryptographic salt
 //! - `srp_derive_private_key()` - Derive private key x = H(salt | H(identity | ":" | password_hash))
+//! - [`srp_derive_private_key_with_kdf()`] - Same, but stretches the raw password through a
+//!   memory-hard [`KdfAlgorithm`] first instead of taking a pre-hashed `password_hash`
 //! - `srp_derive_verifier()` - Derive verifier v = g^x mod N
 //! - `srp_generate_ephemeral()` - Generate client ephemeral key pair (A, a)
 //! - `srp_derive_session()` - Derive session key and proof from server response
@@ -16,6 +23,36 @@
 //! # Server Operations
 //! - `srp_generate_ephemeral_server()` - Generate server ephemeral key pair (B, b)
 //! - `srp_derive_session_server()` - Verify client proof and derive session
+//!
+//! # Stateful Handshakes
+//! [`SrpClientHandshake`]/[`SrpServerHandshake`] wrap the client/server
+//! operations above into stepwise objects that own the intermediate state
+//! (ephemeral secret, derived session) across a round trip, so callers don't
+//! have to carry it themselves. They're thin wrappers fixed to [`DefaultHash`];
+//! use the free functions directly for a different digest.
+//!
+//! # Password Hashing
+//! - `argon2_hash_password()` - Fixed-parameter Argon2id hash (original API, kept for
+//!   backward compatibility with existing stored credentials)
+//! - [`kdf_hash_password()`]/[`kdf_verify_password()`] - Pluggable KDF (Argon2id, scrypt,
+//!   or PBKDF2-HMAC-SHA256) that records its algorithm and parameters in a PHC string, so
+//!   an account's KDF can be upgraded without breaking credentials hashed under an older one
+//!
+//! # Post-Handshake Key Export
+//! - [`srp_export_psk()`] - Expands a completed session's key `K` via HKDF-SHA256 into an
+//!   RFC 4279 TLS-PSK identity/key pair, scoped to a caller-supplied label
+//!
+//! # Master-Password Rotation
+//! - [`rotation::srp_rotate_credentials()`] - Re-derives the salt/password hash/private
+//!   key/verifier for a new password and re-wraps the vault's symmetric key under the new
+//!   password hash in one call, so a rotation can't be left half-applied - see
+//!   [`keywrap`] for the wrapping format
+
+mod handshake;
+mod kdf;
+mod keywrap;
+mod psk;
+mod rotation;
 
 use digest::Digest;
 use num_bigint::BigUint;
@@ -23,15 +60,90 @@ use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use sha2::Sha256;
 use srp::client::SrpClient;
-use srp::groups::G_2048;
+use srp::groups::{G_1024, G_1536, G_2048, G_3072, G_4096, G_6144, G_8192};
 use srp::server::SrpServer;
 use thiserror::Error;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+pub use handshake::{SrpClientHandshake, SrpServerHandshake};
+pub use kdf::{kdf_hash_password, kdf_verify_password, KdfAlgorithm};
+pub use psk::{srp_export_psk, SrpPsk};
+pub use rotation::{srp_rotate_credentials, srp_rotate_credentials_json, SrpRotationInput, SrpRotationOutput};
+
+/// Default hash algorithm for every generic `D: Digest` parameter in this
+/// module, matching its original fixed behavior. The uniffi FFI bindings
+/// always use this; embedders calling the Rust API directly can pick another
+/// digest (e.g. SHA-1 for interop with classic SRP-6a wire protocols).
+pub type DefaultHash = Sha256;
+
+/// An RFC 5054 standard safe-prime group. `G2048` is the default, matching
+/// this module's original fixed group, so existing stored verifiers/salts
+/// keep working unchanged; stronger groups are opt-in for new accounts.
+///
+/// Stored verifiers/salts must record which group they belong to - the same
+/// group used with `srp_derive_verifier` must be passed to
+/// `srp_generate_ephemeral_server` and `srp_derive_session_server` for that
+/// account, or the modulus won't match and the session will fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+#[serde(rename_all = "snake_case")]
+pub enum SrpGroup {
+    G1024,
+    G1536,
+    #[default]
+    G2048,
+    G3072,
+    G4096,
+    G6144,
+    G8192,
+}
 
-/// SRP ephemeral key pair (public and secret values).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl SrpGroup {
+    /// The upstream `srp` crate's group parameters (`N` and `g`) for this group.
+    fn params(self) -> &'static srp::types::SrpGroup {
+        match self {
+            SrpGroup::G1024 => &G_1024,
+            SrpGroup::G1536 => &G_1536,
+            SrpGroup::G2048 => &G_2048,
+            SrpGroup::G3072 => &G_3072,
+            SrpGroup::G4096 => &G_4096,
+            SrpGroup::G6144 => &G_6144,
+            SrpGroup::G8192 => &G_8192,
+        }
+    }
+
+    /// Byte length of the group's modulus `N` - every value padded for
+    /// hashing or hex-encoded output (`g`, `A`, `B`, `v`, `S`) pads to this
+    /// length rather than the 2048-bit group's fixed 256 bytes.
+    fn pad_len(self) -> usize {
+        self.params().n.to_bytes_be().len()
+    }
+}
+
+/// How the shared secret `S` is turned into the session key `K`.
+///
+/// `Aliasvault` (the default) is this module's original `K = H(S)`, used by
+/// every stored AliasVault credential. `Rfc5054Interleaved` is the
+/// `K = H_interleave(S)` derivation from RFC 2945 / RFC 5054, required to
+/// interoperate with a standard third-party SRP-6a client or server rather
+/// than only another instance of this module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+#[serde(rename_all = "snake_case")]
+pub enum SrpKeyDerivation {
+    #[default]
+    Aliasvault,
+    Rfc5054Interleaved,
+}
+
+/// SRP ephemeral key pair (public and secret values). `secret` is the
+/// private scalar (`a` or `b`) and is zeroized on drop; `public` (`A`/`B`)
+/// is meant to cross the wire, so it's left out of the wipe.
+#[derive(Debug, Clone, Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
 #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
 pub struct SrpEphemeral {
     /// Public ephemeral value (uppercase hex)
+    #[zeroize(skip)]
     pub public: String,
     /// Secret ephemeral value (uppercase hex)
     pub secret: String,
@@ -58,6 +170,8 @@ pub enum SrpError {
     InvalidParameter(String),
     #[error("Authentication failed: {0}")]
     AuthenticationFailed(String),
+    #[error("Invalid ephemeral public value: {0}")]
+    InvalidEphemeral(String),
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -181,8 +295,8 @@ pub fn srp_generate_salt() -> String {
 /// * `password_hash` - Pre-hashed password as hex string
 ///
 /// # Returns
-/// Private key as uppercase hex string
-pub fn srp_derive_private_key(
+/// Private key as uppercase hex string (`D::output_size()` bytes)
+pub fn srp_derive_private_key<D: Digest>(
     salt: &str,
     identity: &str,
     password_hash: &str,
@@ -190,14 +304,14 @@ pub fn srp_derive_private_key(
     let salt_bytes = hex_to_bytes(salt)?;
 
     // Compute identity hash: H(identity | ":" | password_hash)
-    let mut identity_hasher = Sha256::new();
+    let mut identity_hasher = D::new();
     identity_hasher.update(identity.as_bytes());
     identity_hasher.update(b":");
     identity_hasher.update(password_hash.as_bytes());
     let identity_hash = identity_hasher.finalize();
 
     // Compute x = H(salt | identity_hash)
-    let mut x_hasher = Sha256::new();
+    let mut x_hasher = D::new();
     x_hasher.update(&salt_bytes);
     x_hasher.update(&identity_hash);
     let x = x_hasher.finalize();
@@ -205,32 +319,65 @@ pub fn srp_derive_private_key(
     Ok(bytes_to_hex(&x))
 }
 
+/// Derive the SRP private key (x), stretching `password` through a
+/// memory-hard KDF first instead of taking a pre-hashed, possibly weakly
+/// hashed `password_hash` as given.
+///
+/// SRP's own `x = H(salt | H(I | ":" | P))` only ever runs the fast digest
+/// `D`, so a stolen verifier is only as hard to brute-force as a single
+/// SHA-2/SHA-1 round; delegating the password-stretching step to Argon2id,
+/// scrypt, or PBKDF2 (via [`KdfAlgorithm`]) before that hash closes that gap
+/// without changing the wire protocol - `v = g^x mod N` and the rest of the
+/// handshake are unaffected by how `x` was produced.
+///
+/// # Arguments
+/// * `salt` - Salt as hex string (reused as the KDF's salt)
+/// * `identity` - User identity
+/// * `password` - The user's raw password
+/// * `algorithm` - KDF and parameters to stretch `password` with
+///
+/// # Returns
+/// Private key as uppercase hex string (`D::output_size()` bytes)
+pub fn srp_derive_private_key_with_kdf<D: Digest>(
+    salt: &str,
+    identity: &str,
+    password: &str,
+    algorithm: KdfAlgorithm,
+) -> Result<String, SrpError> {
+    let salt_bytes = hex_to_bytes(salt)?;
+    let stretched = algorithm.derive(password.as_bytes(), &salt_bytes)?;
+    srp_derive_private_key::<D>(salt, identity, &bytes_to_hex(&stretched))
+}
+
 /// Derive the SRP verifier (v) from a private key.
 ///
 /// Formula: v = g^x mod N
 ///
 /// # Arguments
 /// * `private_key` - Private key as hex string
+/// * `group` - RFC 5054 group to derive the verifier against
 ///
 /// # Returns
-/// Verifier as uppercase hex string (256 bytes)
-pub fn srp_derive_verifier(private_key: &str) -> Result<String, SrpError> {
+/// Verifier as uppercase hex string (padded to the group's modulus length)
+pub fn srp_derive_verifier(private_key: &str, group: SrpGroup) -> Result<String, SrpError> {
     let x_bytes = hex_to_bytes(private_key)?;
     let x = BigUint::from_bytes_be(&x_bytes);
+    let params = group.params();
 
     // v = g^x mod N
-    let v = G_2048.g.modpow(&x, &G_2048.n);
+    let v = params.g.modpow(&x, &params.n);
 
-    // Pad to N length (256 bytes for 2048-bit group)
-    let v_bytes = pad_to_length(v.to_bytes_be(), 256);
+    // Pad to N length
+    let v_bytes = pad_to_length(v.to_bytes_be(), group.pad_len());
     Ok(bytes_to_hex(&v_bytes))
 }
 
 /// Generate a client ephemeral key pair.
 ///
 /// Computes A = g^a mod N where a is a random 64-byte secret.
-pub fn srp_generate_ephemeral() -> SrpEphemeral {
-    let client = SrpClient::<Sha256>::new(&G_2048);
+pub fn srp_generate_ephemeral<D: Digest>(group: SrpGroup) -> SrpEphemeral {
+    let params = group.params();
+    let client = SrpClient::<D>::new(params);
 
     // Generate 64 bytes of random data for the secret
     let a = generate_random_bytes(64);
@@ -238,8 +385,8 @@ pub fn srp_generate_ephemeral() -> SrpEphemeral {
     // Compute public ephemeral A = g^a mod N
     let a_pub = client.compute_public_ephemeral(&a);
 
-    // Pad to N length (256 bytes for 2048-bit group)
-    let a_pub_padded = pad_to_length(a_pub, 256);
+    // Pad to N length
+    let a_pub_padded = pad_to_length(a_pub, group.pad_len());
 
     SrpEphemeral {
         public: bytes_to_hex(&a_pub_padded),
@@ -249,7 +396,9 @@ pub fn srp_generate_ephemeral() -> SrpEphemeral {
 
 /// Derive the client session from server response.
 ///
-/// Computes the shared session key K and client proof M1.
+/// Computes the shared session key K and client proof M1. `D` must match the
+/// digest used for `server_public`/`srp_generate_ephemeral_server` on this
+/// account, or the derived proof won't match the server's.
 ///
 /// # Arguments
 /// * `client_secret` - Client secret ephemeral (a) as hex string
@@ -257,62 +406,73 @@ pub fn srp_generate_ephemeral() -> SrpEphemeral {
 /// * `salt` - Salt as hex string
 /// * `identity` - User identity
 /// * `private_key` - Private key (x) as hex string
+/// * `group` - RFC 5054 group this account's verifier was derived against
+/// * `mode` - How to derive K from S; must match the server's `mode` for
+///   this session, or the proofs won't agree
 ///
 /// # Returns
 /// Session with proof (M1) and key (K), or error if B is invalid
-pub fn srp_derive_session(
+pub fn srp_derive_session<D: Digest>(
     client_secret: &str,
     server_public: &str,
     salt: &str,
     identity: &str,
     private_key: &str,
+    group: SrpGroup,
+    mode: SrpKeyDerivation,
 ) -> Result<SrpSession, SrpError> {
-    let a = hex_to_bytes(client_secret)?;
+    let a = Zeroizing::new(hex_to_bytes(client_secret)?);
     let b_pub = hex_to_bytes(server_public)?;
     let salt_bytes = hex_to_bytes(salt)?;
-    let x_bytes = hex_to_bytes(private_key)?;
+    let x_bytes = Zeroizing::new(hex_to_bytes(private_key)?);
+    let params = group.params();
+    let pad_len = group.pad_len();
 
-    let client = SrpClient::<Sha256>::new(&G_2048);
+    let client = SrpClient::<D>::new(params);
 
     // Convert to BigUint for calculations
     let a_big = BigUint::from_bytes_be(&a);
     let a_pub = client.compute_a_pub(&a_big);
     let b_pub_big = BigUint::from_bytes_be(&b_pub);
 
-    // Check for malicious B (B mod N must not be 0)
-    if &b_pub_big % &G_2048.n == BigUint::default() {
-        return Err(SrpError::InvalidParameter(
-            "server public ephemeral is invalid".to_string(),
+    // Check for malicious B (B mod N must not be 0, or S collapses to a
+    // known constant and an attacker could authenticate without the password)
+    if &b_pub_big % &params.n == BigUint::default() {
+        return Err(SrpError::InvalidEphemeral(
+            "server public ephemeral is degenerate (B mod N == 0)".to_string(),
         ));
     }
 
     // Pad A and B to N length for hashing
-    let a_pub_bytes = pad_to_length(a_pub.to_bytes_be(), 256);
-    let b_pub_bytes = pad_to_length(b_pub, 256);
+    let a_pub_bytes = pad_to_length(a_pub.to_bytes_be(), pad_len);
+    let b_pub_bytes = pad_to_length(b_pub, pad_len);
 
     // Compute u = H(A | B)
-    let u = compute_u(&a_pub_bytes, &b_pub_bytes);
+    let u = compute_u::<D>(&a_pub_bytes, &b_pub_bytes);
+    if u == BigUint::default() {
+        return Err(SrpError::InvalidEphemeral(
+            "scrambling parameter u is degenerate (u == 0)".to_string(),
+        ));
+    }
 
-    // Compute k = H(N | g)
-    let k = compute_k();
+    // Compute k = H(N | PAD(g))
+    let k = compute_k::<D>(group);
 
     // x as BigUint
     let x = BigUint::from_bytes_be(&x_bytes);
 
     // S = (B - k*g^x)^(a + u*x) mod N
-    let kg_x = (&k * G_2048.g.modpow(&x, &G_2048.n)) % &G_2048.n;
-    let base = ((&G_2048.n + &b_pub_big) - &kg_x) % &G_2048.n;
+    let kg_x = (&k * params.g.modpow(&x, &params.n)) % &params.n;
+    let base = ((&params.n + &b_pub_big) - &kg_x) % &params.n;
     let exp = (&u * &x) + &a_big;
-    let s = base.modpow(&exp, &G_2048.n);
+    let s = base.modpow(&exp, &params.n);
 
-    // K = H(S)
-    let s_bytes = pad_to_length(s.to_bytes_be(), 256);
-    let mut key_hasher = Sha256::new();
-    key_hasher.update(&s_bytes);
-    let key = key_hasher.finalize();
+    // K = H(S) or K = H_interleave(S), depending on `mode`
+    let s_bytes = Zeroizing::new(pad_to_length(s.to_bytes_be(), pad_len));
+    let key = Zeroizing::new(compute_session_key::<D>(&s_bytes, mode));
 
     // M1 = H(H(N) XOR H(g) | H(I) | s | A | B | K)
-    let m1 = compute_m1(&a_pub_bytes, &b_pub_bytes, &salt_bytes, identity, &key);
+    let m1 = compute_m1::<D>(&a_pub_bytes, &b_pub_bytes, &salt_bytes, identity, &key, group);
 
     Ok(SrpSession {
         proof: bytes_to_hex(&m1),
@@ -330,10 +490,15 @@ pub fn srp_derive_session(
 ///
 /// # Arguments
 /// * `verifier` - Password verifier (v) as hex string
-pub fn srp_generate_ephemeral_server(verifier: &str) -> Result<SrpEphemeral, SrpError> {
+/// * `group` - RFC 5054 group this account's verifier was derived against
+pub fn srp_generate_ephemeral_server<D: Digest>(
+    verifier: &str,
+    group: SrpGroup,
+) -> Result<SrpEphemeral, SrpError> {
     let v_bytes = hex_to_bytes(verifier)?;
+    let params = group.params();
 
-    let server = SrpServer::<Sha256>::new(&G_2048);
+    let server = SrpServer::<D>::new(params);
 
     // Generate 64 bytes of random data for the secret
     let b = generate_random_bytes(64);
@@ -341,8 +506,8 @@ pub fn srp_generate_ephemeral_server(verifier: &str) -> Result<SrpEphemeral, Srp
     // Compute public ephemeral B = k*v + g^b mod N
     let b_pub = server.compute_public_ephemeral(&b, &v_bytes);
 
-    // Pad to N length (256 bytes for 2048-bit group)
-    let b_pub_padded = pad_to_length(b_pub, 256);
+    // Pad to N length
+    let b_pub_padded = pad_to_length(b_pub, group.pad_len());
 
     Ok(SrpEphemeral {
         public: bytes_to_hex(&b_pub_padded),
@@ -352,7 +517,8 @@ pub fn srp_generate_ephemeral_server(verifier: &str) -> Result<SrpEphemeral, Srp
 
 /// Derive and verify the server session from client response.
 ///
-/// Verifies client proof M1 and computes server proof M2.
+/// Verifies client proof M1 and computes server proof M2. `D` must match the
+/// digest the client used to derive `client_proof`.
 ///
 /// # Arguments
 /// * `server_secret` - Server secret ephemeral (b) as hex string
@@ -361,62 +527,78 @@ pub fn srp_generate_ephemeral_server(verifier: &str) -> Result<SrpEphemeral, Srp
 /// * `identity` - User identity
 /// * `verifier` - Password verifier (v) as hex string
 /// * `client_proof` - Client proof (M1) as hex string
+/// * `group` - RFC 5054 group this account's verifier was derived against
+/// * `mode` - How to derive K from S; must match the client's `mode` for
+///   this session, or the proofs won't agree
 ///
 /// # Returns
 /// Session with proof (M2) and key (K) if verification succeeds, None if M1 is invalid
-pub fn srp_derive_session_server(
+///
+/// # Security
+/// The client proof is compared against the locally computed M1 with
+/// [`subtle::ConstantTimeEq`], not a byte/string `==`, so a forged proof
+/// can't be brute-forced a byte at a time by timing how long rejection takes.
+pub fn srp_derive_session_server<D: Digest>(
     server_secret: &str,
     client_public: &str,
     salt: &str,
     identity: &str,
     verifier: &str,
     client_proof: &str,
+    group: SrpGroup,
+    mode: SrpKeyDerivation,
 ) -> Result<Option<SrpSession>, SrpError> {
-    let b = hex_to_bytes(server_secret)?;
+    let b = Zeroizing::new(hex_to_bytes(server_secret)?);
     let a_pub = hex_to_bytes(client_public)?;
     let salt_bytes = hex_to_bytes(salt)?;
     let v_bytes = hex_to_bytes(verifier)?;
     let client_m1 = hex_to_bytes(client_proof)?;
+    let params = group.params();
+    let pad_len = group.pad_len();
 
     // Convert to BigUint for calculations
     let b_big = BigUint::from_bytes_be(&b);
     let a_pub_big = BigUint::from_bytes_be(&a_pub);
     let v = BigUint::from_bytes_be(&v_bytes);
 
-    // Check for malicious A (A mod N must not be 0)
-    if &a_pub_big % &G_2048.n == BigUint::default() {
-        return Err(SrpError::InvalidParameter(
-            "client public ephemeral is invalid".to_string(),
+    // Check for malicious A (A mod N must not be 0, or S collapses to a
+    // known constant and an attacker could authenticate without the password)
+    if &a_pub_big % &params.n == BigUint::default() {
+        return Err(SrpError::InvalidEphemeral(
+            "client public ephemeral is degenerate (A mod N == 0)".to_string(),
         ));
     }
 
-    // Compute k = H(N | g)
-    let k = compute_k();
+    // Compute k = H(N | PAD(g))
+    let k = compute_k::<D>(group);
 
     // B = k*v + g^b mod N
-    let kv = (&k * &v) % &G_2048.n;
-    let b_pub = (&kv + G_2048.g.modpow(&b_big, &G_2048.n)) % &G_2048.n;
+    let kv = (&k * &v) % &params.n;
+    let b_pub = (&kv + params.g.modpow(&b_big, &params.n)) % &params.n;
 
     // Pad A and B to N length
-    let a_pub_bytes = pad_to_length(a_pub.clone(), 256);
-    let b_pub_bytes = pad_to_length(b_pub.to_bytes_be(), 256);
+    let a_pub_bytes = pad_to_length(a_pub.clone(), pad_len);
+    let b_pub_bytes = pad_to_length(b_pub.to_bytes_be(), pad_len);
 
     // Compute u = H(A | B)
-    let u = compute_u(&a_pub_bytes, &b_pub_bytes);
+    let u = compute_u::<D>(&a_pub_bytes, &b_pub_bytes);
+    if u == BigUint::default() {
+        return Err(SrpError::InvalidEphemeral(
+            "scrambling parameter u is degenerate (u == 0)".to_string(),
+        ));
+    }
 
     // S = (A * v^u)^b mod N
-    let v_u = v.modpow(&u, &G_2048.n);
-    let base = (&a_pub_big * &v_u) % &G_2048.n;
-    let s = base.modpow(&b_big, &G_2048.n);
+    let v_u = v.modpow(&u, &params.n);
+    let base = (&a_pub_big * &v_u) % &params.n;
+    let s = base.modpow(&b_big, &params.n);
 
-    // K = H(S)
-    let s_bytes = pad_to_length(s.to_bytes_be(), 256);
-    let mut key_hasher = Sha256::new();
-    key_hasher.update(&s_bytes);
-    let key = key_hasher.finalize();
+    // K = H(S) or K = H_interleave(S), depending on `mode`
+    let s_bytes = Zeroizing::new(pad_to_length(s.to_bytes_be(), pad_len));
+    let key = Zeroizing::new(compute_session_key::<D>(&s_bytes, mode));
 
     // M1 = H(H(N) XOR H(g) | H(I) | s | A | B | K)
-    let expected_m1 = compute_m1(&a_pub_bytes, &b_pub_bytes, &salt_bytes, identity, &key);
+    let expected_m1 = compute_m1::<D>(&a_pub_bytes, &b_pub_bytes, &salt_bytes, identity, &key, group);
 
     // Verify client proof using constant-time comparison
     use subtle::ConstantTimeEq;
@@ -425,7 +607,7 @@ pub fn srp_derive_session_server(
     }
 
     // M2 = H(A | M1 | K)
-    let m2 = compute_m2(&a_pub_bytes, &expected_m1, &key);
+    let m2 = compute_m2::<D>(&a_pub_bytes, &expected_m1, &key);
 
     Ok(Some(SrpSession {
         proof: bytes_to_hex(&m2),
@@ -438,19 +620,59 @@ pub fn srp_derive_session_server(
 // ═══════════════════════════════════════════════════════════════════════════════
 
 /// Compute u = H(A | B)
-fn compute_u(a_pub: &[u8], b_pub: &[u8]) -> BigUint {
-    let mut hasher = Sha256::new();
+fn compute_u<D: Digest>(a_pub: &[u8], b_pub: &[u8]) -> BigUint {
+    let mut hasher = D::new();
     hasher.update(a_pub);
     hasher.update(b_pub);
     BigUint::from_bytes_be(&hasher.finalize())
 }
 
+/// Derive the session key K from the shared secret S, per `mode`.
+fn compute_session_key<D: Digest>(s_bytes: &[u8], mode: SrpKeyDerivation) -> Vec<u8> {
+    match mode {
+        SrpKeyDerivation::Aliasvault => {
+            let mut hasher = D::new();
+            hasher.update(s_bytes);
+            hasher.finalize().to_vec()
+        }
+        SrpKeyDerivation::Rfc5054Interleaved => sha_interleave::<D>(s_bytes),
+    }
+}
+
+/// RFC 2945 / RFC 5054 interleaved hash: `K = H_interleave(S)`.
+///
+/// Strips leading zero bytes from the big-endian `S`, drops one more byte if
+/// the remainder is odd-length, splits the result into even- and
+/// odd-indexed bytes, hashes each half separately, then interleaves the two
+/// digests byte by byte. Produces a key twice as long as `D`'s output size.
+fn sha_interleave<D: Digest>(s_bytes: &[u8]) -> Vec<u8> {
+    let first_nonzero = s_bytes.iter().position(|&b| b != 0).unwrap_or(s_bytes.len());
+    let mut t = &s_bytes[first_nonzero..];
+    if t.len() % 2 != 0 {
+        t = &t[1..];
+    }
+
+    let even_bytes: Vec<u8> = t.iter().step_by(2).copied().collect();
+    let odd_bytes: Vec<u8> = t.iter().skip(1).step_by(2).copied().collect();
+
+    let mut even_hasher = D::new();
+    even_hasher.update(&even_bytes);
+    let he = even_hasher.finalize();
+
+    let mut odd_hasher = D::new();
+    odd_hasher.update(&odd_bytes);
+    let hf = odd_hasher.finalize();
+
+    he.iter().zip(hf.iter()).flat_map(|(a, b)| [*a, *b]).collect()
+}
+
 /// Compute k = H(N | PAD(g))
-fn compute_k() -> BigUint {
-    let mut hasher = Sha256::new();
-    hasher.update(&G_2048.n.to_bytes_be());
+fn compute_k<D: Digest>(group: SrpGroup) -> BigUint {
+    let params = group.params();
+    let mut hasher = D::new();
+    hasher.update(&params.n.to_bytes_be());
     // Pad g to the same length as N
-    let g_padded = pad_to_length(G_2048.g.to_bytes_be(), 256);
+    let g_padded = pad_to_length(params.g.to_bytes_be(), group.pad_len());
     hasher.update(&g_padded);
     BigUint::from_bytes_be(&hasher.finalize())
 }
@@ -458,27 +680,36 @@ fn compute_k() -> BigUint {
 /// Compute M1 = H(H(N) XOR H(g) | H(I) | s | A | B | K)
 ///
 /// Note: H(g) uses g without padding, unlike k = H(N, PAD(g))
-fn compute_m1(a_pub: &[u8], b_pub: &[u8], salt: &[u8], identity: &str, key: &[u8]) -> Vec<u8> {
+fn compute_m1<D: Digest>(
+    a_pub: &[u8],
+    b_pub: &[u8],
+    salt: &[u8],
+    identity: &str,
+    key: &[u8],
+    group: SrpGroup,
+) -> Vec<u8> {
+    let params = group.params();
+
     // H(N)
-    let mut n_hasher = Sha256::new();
-    n_hasher.update(&G_2048.n.to_bytes_be());
+    let mut n_hasher = D::new();
+    n_hasher.update(&params.n.to_bytes_be());
     let h_n = n_hasher.finalize();
 
     // H(g) - NOT padded
-    let mut g_hasher = Sha256::new();
-    g_hasher.update(&G_2048.g.to_bytes_be());
+    let mut g_hasher = D::new();
+    g_hasher.update(&params.g.to_bytes_be());
     let h_g = g_hasher.finalize();
 
     // H(N) XOR H(g)
     let h_n_xor_h_g: Vec<u8> = h_n.iter().zip(h_g.iter()).map(|(a, b)| a ^ b).collect();
 
     // H(I)
-    let mut i_hasher = Sha256::new();
+    let mut i_hasher = D::new();
     i_hasher.update(identity.as_bytes());
     let h_i = i_hasher.finalize();
 
     // M1 = H(H(N) XOR H(g) | H(I) | s | A | B | K)
-    let mut m1_hasher = Sha256::new();
+    let mut m1_hasher = D::new();
     m1_hasher.update(&h_n_xor_h_g);
     m1_hasher.update(&h_i);
     m1_hasher.update(salt);
@@ -490,8 +721,8 @@ fn compute_m1(a_pub: &[u8], b_pub: &[u8], salt: &[u8], identity: &str, key: &[u8
 }
 
 /// Compute M2 = H(A | M1 | K)
-fn compute_m2(a_pub: &[u8], m1: &[u8], key: &[u8]) -> Vec<u8> {
-    let mut m2_hasher = Sha256::new();
+fn compute_m2<D: Digest>(a_pub: &[u8], m1: &[u8], key: &[u8]) -> Vec<u8> {
+    let mut m2_hasher = D::new();
     m2_hasher.update(a_pub);
     m2_hasher.update(m1);
     m2_hasher.update(key);
@@ -505,6 +736,7 @@ fn compute_m2(a_pub: &[u8], m1: &[u8], key: &[u8]) -> Vec<u8> {
 /// Verify the server's session proof (M2) on the client side.
 ///
 /// This confirms that the server successfully derived the same session key.
+/// `D` must be the same digest used to derive `session_key` and `client_proof`.
 ///
 /// # Arguments
 /// * `client_public` - Client public ephemeral (A) as hex string
@@ -514,7 +746,12 @@ fn compute_m2(a_pub: &[u8], m1: &[u8], key: &[u8]) -> Vec<u8> {
 ///
 /// # Returns
 /// True if verification succeeds, false otherwise
-pub fn srp_verify_session(
+///
+/// # Security
+/// Uses [`subtle::ConstantTimeEq`] rather than a byte/string `==`, so a
+/// forged server proof can't be brute-forced a byte at a time by timing
+/// how long rejection takes.
+pub fn srp_verify_session<D: Digest>(
     client_public: &str,
     client_proof: &str,
     session_key: &str,
@@ -526,7 +763,7 @@ pub fn srp_verify_session(
     let server_m2_bytes = hex_to_bytes(server_proof)?;
 
     // Compute expected M2 = H(A | M1 | K)
-    let expected_m2 = compute_m2(&a_pub_bytes, &m1_bytes, &key_bytes);
+    let expected_m2 = compute_m2::<D>(&a_pub_bytes, &m1_bytes, &key_bytes);
 
     // Constant-time comparison for security
     use subtle::ConstantTimeEq;
@@ -555,7 +792,7 @@ mod tests {
         let identity = "testuser";
         let password_hash = "AABBCCDD";
 
-        let private_key = srp_derive_private_key(salt, identity, password_hash).unwrap();
+        let private_key = srp_derive_private_key::<Sha256>(salt, identity, password_hash).unwrap();
         eprintln!("Rust Private Key: {}", private_key);
 
         let expected = "ACD81DF26882B20336CF2A8CDE3CABA35BA359805FDFC4567EA7BD74E8302473";
@@ -565,20 +802,93 @@ mod tests {
         assert!(hex_to_bytes(&private_key).is_ok());
 
         // Same inputs should produce same output
-        let private_key2 = srp_derive_private_key(salt, identity, password_hash).unwrap();
+        let private_key2 = srp_derive_private_key::<Sha256>(salt, identity, password_hash).unwrap();
         assert_eq!(private_key, private_key2);
 
         assert_eq!(private_key.to_uppercase(), expected);
     }
 
+    #[test]
+    fn test_derive_private_key_with_kdf_is_deterministic_and_password_sensitive() {
+        let salt = srp_generate_salt();
+        let identity = "testuser";
+        let algorithm = KdfAlgorithm::Pbkdf2Sha256 { iterations: 1_000 };
+
+        let key1 = srp_derive_private_key_with_kdf::<Sha256>(&salt, identity, "hunter2", algorithm).unwrap();
+        let key2 = srp_derive_private_key_with_kdf::<Sha256>(&salt, identity, "hunter2", algorithm).unwrap();
+        let key3 = srp_derive_private_key_with_kdf::<Sha256>(&salt, identity, "hunter3", algorithm).unwrap();
+
+        assert_eq!(key1, key2);
+        assert_ne!(key1, key3);
+        assert_eq!(hex_to_bytes(&key1).unwrap().len(), 32);
+    }
+
+    #[test]
+    fn test_derive_private_key_with_kdf_differs_from_raw_hash_entry_point() {
+        // Stretching the password through a KDF first must not collapse
+        // back to the legacy H(identity:password) path - otherwise the KDF
+        // stage would be a no-op.
+        let salt = srp_generate_salt();
+        let identity = "testuser";
+        let password = "hunter2";
+
+        let stretched_key = srp_derive_private_key_with_kdf::<Sha256>(
+            &salt, identity, password, KdfAlgorithm::Pbkdf2Sha256 { iterations: 1_000 },
+        ).unwrap();
+        let raw_key = srp_derive_private_key::<Sha256>(&salt, identity, password).unwrap();
+
+        assert_ne!(stretched_key, raw_key);
+    }
+
+    #[test]
+    fn test_derive_private_key_with_kdf_end_to_end_handshake() {
+        // The KDF-stretched private key must still work as a drop-in `x`
+        // for the rest of the protocol - v, A, B, M1, M2 all unaffected by
+        // how x was produced.
+        let salt = srp_generate_salt();
+        let identity = "testuser@example.com";
+        let algorithm = KdfAlgorithm::Argon2id { m_cost: 8, t_cost: 1, p_cost: 1 };
+
+        let private_key =
+            srp_derive_private_key_with_kdf::<Sha256>(&salt, identity, "correct horse battery staple", algorithm)
+                .unwrap();
+        let verifier = srp_derive_verifier(&private_key, SrpGroup::G2048).unwrap();
+
+        let client_ephemeral = srp_generate_ephemeral::<Sha256>(SrpGroup::G2048);
+        let server_ephemeral = srp_generate_ephemeral_server::<Sha256>(&verifier, SrpGroup::G2048).unwrap();
+
+        let client_session = srp_derive_session::<Sha256>(
+            &client_ephemeral.secret,
+            &server_ephemeral.public,
+            &salt,
+            identity,
+            &private_key,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
+        ).unwrap();
+
+        let server_session = srp_derive_session_server::<Sha256>(
+            &server_ephemeral.secret,
+            &client_ephemeral.public,
+            &salt,
+            identity,
+            &verifier,
+            &client_session.proof,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
+        ).unwrap().expect("valid client proof should produce a session");
+
+        assert_eq!(client_session.key, server_session.key);
+    }
+
     #[test]
     fn test_derive_verifier() {
         let salt = "0A0B0C0D0E0F10111213141516171819";
         let identity = "testuser";
         let password_hash = "AABBCCDD";
 
-        let private_key = srp_derive_private_key(salt, identity, password_hash).unwrap();
-        let verifier = srp_derive_verifier(&private_key).unwrap();
+        let private_key = srp_derive_private_key::<Sha256>(salt, identity, password_hash).unwrap();
+        let verifier = srp_derive_verifier(&private_key, SrpGroup::G2048).unwrap();
         eprintln!("Rust Verifier: {}", verifier);
 
         let expected = "378FAC69B16F469FB21294F7C74429CD288F47E331E8BA02FFD7C36F2914472A9F2A8C69FFEA434C9F78FCA7E7E41CBBF591FFA589460F023EF3A6F7F6B84366458893C52F8A3304E2247C50BDAE13F4463281B8CDCC519DD563A926C93D9A33E08C1DE2EFB6102BD4BFFE97D9DA9A20354393FA041C8C0459D9D11907E11B75DE4F74990CD0364BA3884C697CF548E31707162D033576B96756A9C8B622332AC9631F62D170445CF33A5EF7E1BE82EC949A5F1FD4AAF1767EE861C729E348FD4209F552BEA5A2F059C64985F4DD2495896AE33315F54329192715AB27EA32B0AF56AC8991C9F708260EF3B5D263FA55B6380CDD294F272FFD1DD86116F0C06C";
@@ -592,7 +902,7 @@ mod tests {
 
     #[test]
     fn test_generate_ephemeral() {
-        let ephemeral = srp_generate_ephemeral();
+        let ephemeral = srp_generate_ephemeral::<Sha256>(SrpGroup::G2048);
 
         // Public should be 256 bytes = 512 hex chars
         assert_eq!(ephemeral.public.len(), 512);
@@ -604,15 +914,26 @@ mod tests {
         assert!(hex_to_bytes(&ephemeral.secret).is_ok());
     }
 
+    #[test]
+    fn test_ephemeral_secret_zeroizes_but_public_does_not() {
+        let mut ephemeral = srp_generate_ephemeral::<Sha256>(SrpGroup::G2048);
+        let public_before = ephemeral.public.clone();
+
+        ephemeral.zeroize();
+
+        assert!(ephemeral.secret.bytes().all(|b| b == 0));
+        assert_eq!(ephemeral.public, public_before);
+    }
+
     #[test]
     fn test_generate_ephemeral_server() {
         // First derive a verifier
         let salt = srp_generate_salt();
-        let private_key = srp_derive_private_key(&salt, "testuser", "PASSWORDHASH").unwrap();
-        let verifier = srp_derive_verifier(&private_key).unwrap();
+        let private_key = srp_derive_private_key::<Sha256>(&salt, "testuser", "PASSWORDHASH").unwrap();
+        let verifier = srp_derive_verifier(&private_key, SrpGroup::G2048).unwrap();
 
         // Generate server ephemeral
-        let ephemeral = srp_generate_ephemeral_server(&verifier).unwrap();
+        let ephemeral = srp_generate_ephemeral_server::<Sha256>(&verifier, SrpGroup::G2048).unwrap();
 
         // Public should be 256 bytes = 512 hex chars
         assert_eq!(ephemeral.public.len(), 512);
@@ -630,11 +951,11 @@ mod tests {
         let expected_private_key = "37D921B103087DDBCFEE50E240DBF5904BBC021BD07391F206CA74BE5430D79B";
         let expected_verifier = "603ABD0F6C5494976B140BBF29D988989FD88654438994959D851C83FC891FA22C81B7CD3B1BBC5472651473183789A4DB5454D530BDEF328DCBA19C112ED266584D8750AEFDCFC0076FD40B3E16773672994C7CB56B4F6CD5FCA47927F9688483937890054D208DDBDD5117F18461B6AD7A279495583B7D99CDC1EB678E9402171F43DC7732549B5A5A3A4A2BF586686887E09D1DED55A7945C20F4DB62915DCF7FD4D7ECED87758B3E19E25CFC668FDB92FCE15E9452DE7F78BDB9BC80DE25882769870E156B2860A169F33045298CEC7700975E3EF4AAE5B41CE6086E2593EDCF2BEA8F3B613258259197C4AE8A67055ED5546C83F6EF035BA788EC63A1AE";
 
-        let private_key = srp_derive_private_key(salt, identity, password_hash).unwrap();
+        let private_key = srp_derive_private_key::<Sha256>(salt, identity, password_hash).unwrap();
         eprintln!("Rust Private Key: {}", private_key);
         assert_eq!(private_key.to_uppercase(), expected_private_key);
 
-        let verifier = srp_derive_verifier(&private_key).unwrap();
+        let verifier = srp_derive_verifier(&private_key, SrpGroup::G2048).unwrap();
         eprintln!("Rust Verifier: {}", verifier);
         assert_eq!(verifier.to_uppercase(), expected_verifier);
     }
@@ -646,7 +967,7 @@ mod tests {
         let identity = "testuser";
         let password_hash = "AABBCCDDEEFF00112233445566778899AABBCCDDEEFF00112233445566778899";
 
-        let private_key = srp_derive_private_key(salt, identity, password_hash).unwrap();
+        let private_key = srp_derive_private_key::<Sha256>(salt, identity, password_hash).unwrap();
 
         let client_secret = "89697cc13c1cea1f44c5f6b3f8f0cb7ce28246c80de10ca5d4976575dbcb0318";
         let server_public = "523d0e314fccaace5ad5007357b07bb2fb2c5f566be0b812cbe4ffa65adc5bdd5cd59d9ca921b7491481d2963733513968e7bea637a733665f8e9fb7a18ba613a03740eed9ea3795489659a486cd87352054ed49f0636bb2605b8d836a459151cb670d35e8377202d9e1569bf88d0c86bd83d303d8775a65867b68fc7f9a9d5d59c76c413cb1b4d33f1d5eb784d1d18a5705800729a5d566548297c3b84ec1077c4546ab3c9b159a6d6c7265cdc784f36f731fa371e14bc506a544713591579d0a6952c2539746963434f0e97a024c0e93701008e4c54b620a9259d071b88c0a4cf102eaa22732ecfcd1fd23a81ee180074db1b5cee1b3e9172f76153f8d46bc";
@@ -654,12 +975,14 @@ mod tests {
         let expected_session_key = "AD713F5D8F520B7B9413CDD9EF6D9B5FE37F23A9B62C5E2B90D2291F8C3A9E6F";
         let expected_session_proof = "698D0DA7137A0FC4A55B49525C1312ADCD07788E8CD5FFF5BD195B3C17B6B3DF";
 
-        let session = srp_derive_session(
+        let session = srp_derive_session::<Sha256>(
             client_secret,
             server_public,
             salt,
             identity,
             &private_key,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
         ).unwrap();
 
         eprintln!("Rust Session Key: {}", session.key);
@@ -679,11 +1002,11 @@ mod tests {
         let expected_private_key = "352C41C945185EDC02EBA1087A02D06A686A194D3542AE174B4F75F340E4E02E";
         let expected_verifier = "8612168CF700A1CBAE568175B1BDD9B93874A9029B2EA34126910EABFE7DCEA57345560AD96754E1C5A5A2272F1C794D7C6A7D5A756FD37EF78170A3162051035D115AA376F85330701586A714C97413F84BAE12A87497357C0483E443B7D3B75B3C19BCF845ABD38956D2EAEFE733DC696D88277245DC7E25C9013D77053F82E9400F6918BF58176D536EB7D90572A645790E6F5660FD0FB8D5673B584F1F33F06C824CA1CF246BED84E228745CD4ABC1184E5057D03191AB9253F86A407970A4578DC6763D7D42AF2CB71C79F60BB71CA16CF98A17E4F3D62BE8396593427487115163B668A8E0069487C763342B58EFAF9499EBB87DE07E52836B3DF4F28C";
 
-        let private_key = srp_derive_private_key(salt, username, password_hash).unwrap();
+        let private_key = srp_derive_private_key::<Sha256>(salt, username, password_hash).unwrap();
         eprintln!("Rust Private Key: {}", private_key);
         assert_eq!(private_key.to_uppercase(), expected_private_key);
 
-        let verifier = srp_derive_verifier(&private_key).unwrap();
+        let verifier = srp_derive_verifier(&private_key, SrpGroup::G2048).unwrap();
         eprintln!("Rust Verifier: {}", verifier);
         assert_eq!(verifier.to_uppercase(), expected_verifier);
 
@@ -694,12 +1017,14 @@ mod tests {
         let expected_session_key = "7564C550D5BF148D17B33C251B71EA2E0CD96D70E207B58622D9FF78BEE609A4";
         let expected_session_proof = "87BF2829F780EF88C1BFB63F39547DAA3CC787B40978C27CDC50FDEBFD324470";
 
-        let session = srp_derive_session(
+        let session = srp_derive_session::<Sha256>(
             client_secret,
             server_public,
             salt,
             username,
             &private_key,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
         ).unwrap();
 
         eprintln!("Rust Session Key: {}", session.key);
@@ -716,32 +1041,36 @@ mod tests {
         let identity = "testuser@example.com";
         let password_hash = "0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF";
 
-        let private_key = srp_derive_private_key(&salt, identity, password_hash).unwrap();
-        let verifier = srp_derive_verifier(&private_key).unwrap();
+        let private_key = srp_derive_private_key::<Sha256>(&salt, identity, password_hash).unwrap();
+        let verifier = srp_derive_verifier(&private_key, SrpGroup::G2048).unwrap();
 
         // 2. Login: Client generates ephemeral
-        let client_ephemeral = srp_generate_ephemeral();
+        let client_ephemeral = srp_generate_ephemeral::<Sha256>(SrpGroup::G2048);
 
         // 3. Server generates ephemeral and sends to client
-        let server_ephemeral = srp_generate_ephemeral_server(&verifier).unwrap();
+        let server_ephemeral = srp_generate_ephemeral_server::<Sha256>(&verifier, SrpGroup::G2048).unwrap();
 
         // 4. Client derives session
-        let client_session = srp_derive_session(
+        let client_session = srp_derive_session::<Sha256>(
             &client_ephemeral.secret,
             &server_ephemeral.public,
             &salt,
             identity,
             &private_key,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
         ).unwrap();
 
         // 5. Server verifies client proof and derives session
-        let server_session = srp_derive_session_server(
+        let server_session = srp_derive_session_server::<Sha256>(
             &server_ephemeral.secret,
             &client_ephemeral.public,
             &salt,
             identity,
             &verifier,
             &client_session.proof,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
         ).unwrap();
 
         // Server should successfully verify and return a session
@@ -752,6 +1081,303 @@ mod tests {
         assert_eq!(client_session.key, server_session.key);
     }
 
+    /// Same flow as `test_full_srp_flow`, but on a non-default group, to prove
+    /// the padding/modulus is actually threaded through rather than silently
+    /// falling back to `G2048`.
+    #[test]
+    fn test_full_srp_flow_with_non_default_group() {
+        let group = SrpGroup::G4096;
+
+        // 1. Registration: Generate salt and verifier
+        let salt = srp_generate_salt();
+        let identity = "testuser@example.com";
+        let password_hash = "0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF";
+
+        let private_key = srp_derive_private_key::<Sha256>(&salt, identity, password_hash).unwrap();
+        let verifier = srp_derive_verifier(&private_key, group).unwrap();
+
+        // 2. Login: Client generates ephemeral
+        let client_ephemeral = srp_generate_ephemeral::<Sha256>(group);
+
+        // 3. Server generates ephemeral and sends to client
+        let server_ephemeral = srp_generate_ephemeral_server::<Sha256>(&verifier, group).unwrap();
+
+        // 4. Client derives session
+        let client_session = srp_derive_session::<Sha256>(
+            &client_ephemeral.secret,
+            &server_ephemeral.public,
+            &salt,
+            identity,
+            &private_key,
+            group,
+            SrpKeyDerivation::Aliasvault,
+        ).unwrap();
+
+        // 5. Server verifies client proof and derives session
+        let server_session = srp_derive_session_server::<Sha256>(
+            &server_ephemeral.secret,
+            &client_ephemeral.public,
+            &salt,
+            identity,
+            &verifier,
+            &client_session.proof,
+            group,
+            SrpKeyDerivation::Aliasvault,
+        ).unwrap();
+
+        assert!(server_session.is_some());
+        let server_session = server_session.unwrap();
+
+        // Both should have the same session key, and it should be derived from
+        // a 4096-bit modulus rather than G2048's 2048-bit one.
+        assert_eq!(client_session.key, server_session.key);
+        assert_eq!(hex_to_bytes(&client_session.key).unwrap().len(), 32);
+    }
+
+    /// A malicious `B` congruent to 0 mod N collapses S to a known constant,
+    /// letting an attacker authenticate without knowing the password. Must
+    /// be rejected as `InvalidEphemeral`, not processed into a session.
+    #[test]
+    fn test_degenerate_server_public_rejected() {
+        let n_hex = bytes_to_hex(&SrpGroup::G2048.params().n.to_bytes_be());
+        let two_n_hex = bytes_to_hex(&(&SrpGroup::G2048.params().n * BigUint::from(2u32)).to_bytes_be());
+
+        for malicious_b in ["00", n_hex.as_str(), two_n_hex.as_str()] {
+            let result = srp_derive_session::<Sha256>(
+                &srp_generate_ephemeral::<Sha256>(SrpGroup::G2048).secret,
+                malicious_b,
+                &srp_generate_salt(),
+                "testuser",
+                "AABBCCDD",
+                SrpGroup::G2048,
+                SrpKeyDerivation::Aliasvault,
+            );
+
+            assert!(
+                matches!(result, Err(SrpError::InvalidEphemeral(_))),
+                "B = {malicious_b} should be rejected as a degenerate ephemeral"
+            );
+        }
+    }
+
+    /// Same attack from the server's side: a malicious `A` congruent to 0
+    /// mod N must be rejected as `InvalidEphemeral`, not processed.
+    #[test]
+    fn test_degenerate_client_public_rejected() {
+        let salt = srp_generate_salt();
+        let identity = "testuser";
+        let password_hash = "0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF";
+        let private_key = srp_derive_private_key::<Sha256>(&salt, identity, password_hash).unwrap();
+        let verifier = srp_derive_verifier(&private_key, SrpGroup::G2048).unwrap();
+        let server_ephemeral = srp_generate_ephemeral_server::<Sha256>(&verifier, SrpGroup::G2048).unwrap();
+
+        let n_hex = bytes_to_hex(&SrpGroup::G2048.params().n.to_bytes_be());
+        let two_n_hex = bytes_to_hex(&(&SrpGroup::G2048.params().n * BigUint::from(2u32)).to_bytes_be());
+
+        for malicious_a in ["00", n_hex.as_str(), two_n_hex.as_str()] {
+            let result = srp_derive_session_server::<Sha256>(
+                &server_ephemeral.secret,
+                malicious_a,
+                &salt,
+                identity,
+                &verifier,
+                "DEADBEEF",
+                SrpGroup::G2048,
+                SrpKeyDerivation::Aliasvault,
+            );
+
+            assert!(
+                matches!(result, Err(SrpError::InvalidEphemeral(_))),
+                "A = {malicious_a} should be rejected as a degenerate ephemeral"
+            );
+        }
+    }
+
+    /// A full handshake must succeed for every RFC 5054 group, not just the
+    /// 2048-bit default most other tests exercise - each group's modulus
+    /// length drives `pad_to_length` differently, so this is the regression
+    /// test for "only the default group's padding was ever exercised".
+    #[test]
+    fn test_full_srp_flow_across_all_groups() {
+        for group in [
+            SrpGroup::G1024,
+            SrpGroup::G1536,
+            SrpGroup::G2048,
+            SrpGroup::G3072,
+            SrpGroup::G4096,
+            SrpGroup::G6144,
+            SrpGroup::G8192,
+        ] {
+            let salt = srp_generate_salt();
+            let identity = "testuser@example.com";
+            let password_hash = "0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF";
+
+            let private_key = srp_derive_private_key::<Sha256>(&salt, identity, password_hash).unwrap();
+            let verifier = srp_derive_verifier(&private_key, group).unwrap();
+
+            let client_ephemeral = srp_generate_ephemeral::<Sha256>(group);
+            let server_ephemeral = srp_generate_ephemeral_server::<Sha256>(&verifier, group).unwrap();
+
+            let client_session = srp_derive_session::<Sha256>(
+                &client_ephemeral.secret,
+                &server_ephemeral.public,
+                &salt,
+                identity,
+                &private_key,
+                group,
+                SrpKeyDerivation::Aliasvault,
+            ).unwrap();
+
+            let server_session = srp_derive_session_server::<Sha256>(
+                &server_ephemeral.secret,
+                &client_ephemeral.public,
+                &salt,
+                identity,
+                &verifier,
+                &client_session.proof,
+                group,
+                SrpKeyDerivation::Aliasvault,
+            ).unwrap().unwrap_or_else(|| panic!("{group:?} should produce a valid session"));
+
+            assert_eq!(client_session.key, server_session.key, "{group:?} session keys should match");
+        }
+    }
+
+    /// A client and server using mismatched groups for the same account must
+    /// not silently succeed - the moduli differ, so the proofs cannot match.
+    #[test]
+    fn test_mismatched_groups_fail() {
+        let salt = srp_generate_salt();
+        let identity = "testuser@example.com";
+        let password_hash = "0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF";
+
+        let private_key = srp_derive_private_key::<Sha256>(&salt, identity, password_hash).unwrap();
+        let verifier = srp_derive_verifier(&private_key, SrpGroup::G3072).unwrap();
+
+        let client_ephemeral = srp_generate_ephemeral::<Sha256>(SrpGroup::G3072);
+        let server_ephemeral = srp_generate_ephemeral_server::<Sha256>(&verifier, SrpGroup::G3072).unwrap();
+
+        // Client mistakenly derives its session against G2048 instead of the
+        // account's actual group (G3072).
+        let client_session = srp_derive_session::<Sha256>(
+            &client_ephemeral.secret,
+            &server_ephemeral.public,
+            &salt,
+            identity,
+            &private_key,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
+        ).unwrap();
+
+        let server_session = srp_derive_session_server::<Sha256>(
+            &server_ephemeral.secret,
+            &client_ephemeral.public,
+            &salt,
+            identity,
+            &verifier,
+            &client_session.proof,
+            SrpGroup::G3072,
+            SrpKeyDerivation::Aliasvault,
+        ).unwrap();
+
+        assert!(server_session.is_none());
+    }
+
+    /// Same flow as `test_full_srp_flow`, but with both sides agreeing on
+    /// SHA-512 instead of the default `Sha256`, to prove the digest is
+    /// actually threaded through rather than silently falling back to it.
+    #[test]
+    fn test_full_srp_flow_with_non_default_digest() {
+        use sha2::Sha512;
+
+        let salt = srp_generate_salt();
+        let identity = "testuser@example.com";
+        let password_hash = "0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF";
+
+        let private_key = srp_derive_private_key::<Sha512>(&salt, identity, password_hash).unwrap();
+        let verifier = srp_derive_verifier(&private_key, SrpGroup::G2048).unwrap();
+
+        let client_ephemeral = srp_generate_ephemeral::<Sha512>(SrpGroup::G2048);
+        let server_ephemeral = srp_generate_ephemeral_server::<Sha512>(&verifier, SrpGroup::G2048).unwrap();
+
+        let client_session = srp_derive_session::<Sha512>(
+            &client_ephemeral.secret,
+            &server_ephemeral.public,
+            &salt,
+            identity,
+            &private_key,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
+        ).unwrap();
+
+        let server_session = srp_derive_session_server::<Sha512>(
+            &server_ephemeral.secret,
+            &client_ephemeral.public,
+            &salt,
+            identity,
+            &verifier,
+            &client_session.proof,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
+        ).unwrap();
+
+        assert!(server_session.is_some());
+        let server_session = server_session.unwrap();
+        assert_eq!(client_session.key, server_session.key);
+
+        // SHA-512 output is twice as long as SHA-256's (64 bytes vs 32).
+        assert_eq!(hex_to_bytes(&client_session.key).unwrap().len(), 64);
+
+        assert!(srp_verify_session::<Sha512>(
+            &client_ephemeral.public,
+            &client_session.proof,
+            &client_session.key,
+            &server_session.proof,
+        ).unwrap());
+    }
+
+    /// A client and server that disagree on the digest must not silently
+    /// succeed - `M1`/`M2`/`K` all depend on the hash, so the proofs can't match.
+    #[test]
+    fn test_mismatched_digests_fail() {
+        use sha2::Sha512;
+
+        let salt = srp_generate_salt();
+        let identity = "testuser@example.com";
+        let password_hash = "0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF";
+
+        let private_key = srp_derive_private_key::<Sha256>(&salt, identity, password_hash).unwrap();
+        let verifier = srp_derive_verifier(&private_key, SrpGroup::G2048).unwrap();
+
+        let client_ephemeral = srp_generate_ephemeral::<Sha256>(SrpGroup::G2048);
+        let server_ephemeral = srp_generate_ephemeral_server::<Sha256>(&verifier, SrpGroup::G2048).unwrap();
+
+        // Client derives its session with SHA-512 instead of the account's
+        // actual digest (SHA-256).
+        let client_session = srp_derive_session::<Sha512>(
+            &client_ephemeral.secret,
+            &server_ephemeral.public,
+            &salt,
+            identity,
+            &private_key,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
+        ).unwrap();
+
+        let server_session = srp_derive_session_server::<Sha256>(
+            &server_ephemeral.secret,
+            &client_ephemeral.public,
+            &salt,
+            identity,
+            &verifier,
+            &client_session.proof,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
+        ).unwrap();
+
+        assert!(server_session.is_none());
+    }
+
     #[test]
     fn test_wrong_password_fails() {
         // Setup with correct credentials
@@ -760,38 +1386,125 @@ mod tests {
         let correct_password_hash = "CORRECT_PASSWORD_HASH_0123456789";
         let wrong_password_hash = "WRONG_PASSWORD_HASH_0123456789AB";
 
-        let correct_private_key = srp_derive_private_key(&salt, identity, correct_password_hash).unwrap();
-        let verifier = srp_derive_verifier(&correct_private_key).unwrap();
+        let correct_private_key = srp_derive_private_key::<Sha256>(&salt, identity, correct_password_hash).unwrap();
+        let verifier = srp_derive_verifier(&correct_private_key, SrpGroup::G2048).unwrap();
 
         // Client uses wrong password
-        let wrong_private_key = srp_derive_private_key(&salt, identity, wrong_password_hash).unwrap();
+        let wrong_private_key = srp_derive_private_key::<Sha256>(&salt, identity, wrong_password_hash).unwrap();
 
-        let client_ephemeral = srp_generate_ephemeral();
-        let server_ephemeral = srp_generate_ephemeral_server(&verifier).unwrap();
+        let client_ephemeral = srp_generate_ephemeral::<Sha256>(SrpGroup::G2048);
+        let server_ephemeral = srp_generate_ephemeral_server::<Sha256>(&verifier, SrpGroup::G2048).unwrap();
 
         // Client derives session with wrong password
-        let client_session = srp_derive_session(
+        let client_session = srp_derive_session::<Sha256>(
             &client_ephemeral.secret,
             &server_ephemeral.public,
             &salt,
             identity,
             &wrong_private_key,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
         ).unwrap();
 
         // Server should reject the client proof
-        let server_session = srp_derive_session_server(
+        let server_session = srp_derive_session_server::<Sha256>(
             &server_ephemeral.secret,
             &client_ephemeral.public,
             &salt,
             identity,
             &verifier,
             &client_session.proof,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
         ).unwrap();
 
         // Server should return None (authentication failed)
         assert!(server_session.is_none());
     }
 
+    #[test]
+    fn test_truncated_client_proof_rejected_without_panic() {
+        // A proof of the wrong length must be rejected like any other
+        // mismatch, not panic the constant-time comparison.
+        let salt = srp_generate_salt();
+        let identity = "testuser";
+        let password_hash = "0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF";
+
+        let private_key = srp_derive_private_key::<Sha256>(&salt, identity, password_hash).unwrap();
+        let verifier = srp_derive_verifier(&private_key, SrpGroup::G2048).unwrap();
+
+        let client_ephemeral = srp_generate_ephemeral::<Sha256>(SrpGroup::G2048);
+        let server_ephemeral = srp_generate_ephemeral_server::<Sha256>(&verifier, SrpGroup::G2048).unwrap();
+
+        let client_session = srp_derive_session::<Sha256>(
+            &client_ephemeral.secret,
+            &server_ephemeral.public,
+            &salt,
+            identity,
+            &private_key,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
+        ).unwrap();
+
+        let truncated_proof = &client_session.proof[..client_session.proof.len() - 8];
+        let server_session = srp_derive_session_server::<Sha256>(
+            &server_ephemeral.secret,
+            &client_ephemeral.public,
+            &salt,
+            identity,
+            &verifier,
+            truncated_proof,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
+        ).unwrap();
+
+        assert!(server_session.is_none());
+    }
+
+    #[test]
+    fn test_truncated_server_proof_rejected_without_panic() {
+        let salt = srp_generate_salt();
+        let identity = "testuser";
+        let password_hash = "0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF";
+
+        let private_key = srp_derive_private_key::<Sha256>(&salt, identity, password_hash).unwrap();
+        let verifier = srp_derive_verifier(&private_key, SrpGroup::G2048).unwrap();
+
+        let client_ephemeral = srp_generate_ephemeral::<Sha256>(SrpGroup::G2048);
+        let server_ephemeral = srp_generate_ephemeral_server::<Sha256>(&verifier, SrpGroup::G2048).unwrap();
+
+        let client_session = srp_derive_session::<Sha256>(
+            &client_ephemeral.secret,
+            &server_ephemeral.public,
+            &salt,
+            identity,
+            &private_key,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
+        ).unwrap();
+
+        let server_session = srp_derive_session_server::<Sha256>(
+            &server_ephemeral.secret,
+            &client_ephemeral.public,
+            &salt,
+            identity,
+            &verifier,
+            &client_session.proof,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
+        ).unwrap().expect("valid client proof should produce a session");
+
+        let truncated_server_proof = &server_session.proof[..server_session.proof.len() - 8];
+        let verified = srp_verify_session::<Sha256>(
+            &client_ephemeral.public,
+            &client_session.proof,
+            &client_session.key,
+            truncated_server_proof,
+        ).unwrap();
+
+        assert!(!verified);
+    }
+
     #[test]
     fn test_hex_conversion() {
         // Test round-trip
@@ -806,4 +1519,178 @@ mod tests {
         let decoded_lower = hex_to_bytes("00010aff10").unwrap();
         assert_eq!(decoded_lower, original);
     }
+
+    #[test]
+    fn test_pad_to_length_left_pads_short_input() {
+        // This is the exact shape of the "1 in 256" interop failure: a
+        // BigUint's minimal big-endian encoding drops a leading zero byte
+        // whenever the value happens to be smaller than the modulus length,
+        // so a naive hash of `to_bytes_be()` silently disagrees with an
+        // implementation that hashes PAD(A)/PAD(B)/PAD(S).
+        let short = vec![0xAB, 0xCD];
+        let padded = pad_to_length(short, 8);
+        assert_eq!(padded, vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xAB, 0xCD]);
+    }
+
+    #[test]
+    fn test_pad_to_length_is_noop_for_full_length_input() {
+        let full = vec![0x01, 0x02, 0x03, 0x04];
+        let padded = pad_to_length(full.clone(), 4);
+        assert_eq!(padded, full);
+    }
+
+    #[test]
+    fn test_pad_to_length_is_noop_when_already_longer_than_target() {
+        // Should never happen for a valid group element, but must not
+        // truncate if it does.
+        let long = vec![0x01, 0x02, 0x03, 0x04, 0x05];
+        let padded = pad_to_length(long.clone(), 4);
+        assert_eq!(padded, long);
+    }
+
+    #[test]
+    fn test_missing_pad_would_change_m1() {
+        // Demonstrates why padding is load-bearing: an ephemeral whose
+        // minimal encoding is short (leading zero byte in the full-length
+        // form) must hash identically to its padded form, or the 1-in-256
+        // interop failure described in chunk4-2 reappears.
+        let pad_len = SrpGroup::G2048.pad_len();
+        let short_a = vec![0xAB; pad_len - 1];
+        let mut full_a = vec![0x00];
+        full_a.extend_from_slice(&short_a);
+
+        let b_pub = vec![0xCD; pad_len];
+        let salt = vec![0x01, 0x02, 0x03, 0x04];
+        let key = vec![0xEE; 32];
+
+        let m1_from_unpadded = compute_m1::<Sha256>(
+            &short_a, &b_pub, &salt, "testuser", &key, SrpGroup::G2048,
+        );
+        let m1_from_padded = compute_m1::<Sha256>(
+            &pad_to_length(short_a, pad_len), &b_pub, &salt, "testuser", &key, SrpGroup::G2048,
+        );
+
+        // Hashing the un-padded (shorter) A produces a different M1 than
+        // hashing its padded form - proving every call site must route A
+        // (and B, S) through `pad_to_length` before hashing, exactly as
+        // `srp_derive_session`/`srp_derive_session_server` already do.
+        assert_ne!(bytes_to_hex(&m1_from_unpadded), bytes_to_hex(&m1_from_padded));
+        assert_eq!(bytes_to_hex(&m1_from_padded), bytes_to_hex(&compute_m1::<Sha256>(
+            &full_a, &b_pub, &salt, "testuser", &key, SrpGroup::G2048,
+        )));
+    }
+
+    /// Known-answer tests for `sha_interleave`, computed independently with a
+    /// reference Python implementation of the RFC 2945 algorithm (strip
+    /// leading zero bytes, drop one more if the remainder is odd-length,
+    /// split into even/odd-indexed halves, hash each half, interleave).
+    #[test]
+    fn test_sha_interleave_known_answers() {
+        use sha1::Sha1;
+
+        // S has a leading zero byte and is even-length after stripping it.
+        let s = hex_to_bytes("00AB34FF0102030405060708090A0B0C0D0E0F101112131415161718191A1B1C").unwrap();
+        let expected_sha1 = "C01C07B9532A9C2AA7D47552F862364969F99A550A84A25CA7D69B44DA42DFA132D7ECAEE6353D5D";
+        assert_eq!(bytes_to_hex(&sha_interleave::<Sha1>(&s)), expected_sha1);
+
+        let expected_sha256 = "38B631638CB781DF2A4E80BF032FD78465D68A4090CD8F863819314EC5EE983024E79BEE4141C6482D224F08823B8C4D49F26E6606EA5B1B54E27BFED9255B57";
+        assert_eq!(bytes_to_hex(&sha_interleave::<Sha256>(&s)), expected_sha256);
+
+        // S has no leading zero byte and is already even-length.
+        let s2 = hex_to_bytes("AABBCCDDEEFF0011223344556677889900112233").unwrap();
+        let expected_sha1_s2 = "D10030687DCC2DC240D24B73D348FC8D57FE033145C27B05532B8C0CCCAE364452020560C54A3C7D";
+        assert_eq!(bytes_to_hex(&sha_interleave::<Sha1>(&s2)), expected_sha1_s2);
+    }
+
+    /// Full handshake in RFC 5054 conformance mode: the classic SRP-6a demo
+    /// parameters (identity "alice", password "password123", the 1024-bit
+    /// group, SHA-1) with `Rfc5054Interleaved` key derivation. This isn't a
+    /// byte-for-byte replay of the published Appendix B vectors (those also
+    /// fix the ephemeral secrets `a`/`b`, which this crate always generates
+    /// randomly) - it instead proves the interleaved derivation is actually
+    /// wired up end-to-end: both sides agree on a key twice as long as the
+    /// digest output, which `Aliasvault` mode never produces.
+    #[test]
+    fn test_full_srp_flow_rfc5054_conformance_mode() {
+        use sha1::Sha1;
+
+        let group = SrpGroup::G1024;
+        let mode = SrpKeyDerivation::Rfc5054Interleaved;
+        let salt = srp_generate_salt();
+        let identity = "alice";
+        let password_hash = "password123";
+
+        let private_key = srp_derive_private_key::<Sha1>(&salt, identity, password_hash).unwrap();
+        let verifier = srp_derive_verifier(&private_key, group).unwrap();
+
+        let client_ephemeral = srp_generate_ephemeral::<Sha1>(group);
+        let server_ephemeral = srp_generate_ephemeral_server::<Sha1>(&verifier, group).unwrap();
+
+        let client_session = srp_derive_session::<Sha1>(
+            &client_ephemeral.secret,
+            &server_ephemeral.public,
+            &salt,
+            identity,
+            &private_key,
+            group,
+            mode,
+        ).unwrap();
+
+        let server_session = srp_derive_session_server::<Sha1>(
+            &server_ephemeral.secret,
+            &client_ephemeral.public,
+            &salt,
+            identity,
+            &verifier,
+            &client_session.proof,
+            group,
+            mode,
+        ).unwrap();
+
+        assert!(server_session.is_some());
+        let server_session = server_session.unwrap();
+        assert_eq!(client_session.key, server_session.key);
+
+        // SHA-1 output is 20 bytes; the interleaved key is twice that.
+        assert_eq!(hex_to_bytes(&client_session.key).unwrap().len(), 40);
+    }
+
+    /// A client and server that disagree on key-derivation mode must not
+    /// silently succeed - `K` (and therefore `M1`/`M2`) differs between
+    /// `H(S)` and `H_interleave(S)` for the same shared secret.
+    #[test]
+    fn test_mismatched_key_derivation_mode_fails() {
+        let salt = srp_generate_salt();
+        let identity = "testuser@example.com";
+        let password_hash = "0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF";
+
+        let private_key = srp_derive_private_key::<Sha256>(&salt, identity, password_hash).unwrap();
+        let verifier = srp_derive_verifier(&private_key, SrpGroup::G2048).unwrap();
+
+        let client_ephemeral = srp_generate_ephemeral::<Sha256>(SrpGroup::G2048);
+        let server_ephemeral = srp_generate_ephemeral_server::<Sha256>(&verifier, SrpGroup::G2048).unwrap();
+
+        let client_session = srp_derive_session::<Sha256>(
+            &client_ephemeral.secret,
+            &server_ephemeral.public,
+            &salt,
+            identity,
+            &private_key,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Rfc5054Interleaved,
+        ).unwrap();
+
+        let server_session = srp_derive_session_server::<Sha256>(
+            &server_ephemeral.secret,
+            &client_ephemeral.public,
+            &salt,
+            identity,
+            &verifier,
+            &client_session.proof,
+            SrpGroup::G2048,
+            SrpKeyDerivation::Aliasvault,
+        ).unwrap();
+
+        assert!(server_session.is_none());
+    }
 }
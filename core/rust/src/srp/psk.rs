@@ -0,0 +1,108 @@
+//! Derive a TLS-PSK identity/key pair from a completed SRP session.
+//!
+//! Once `srp_derive_session`/`srp_derive_session_server` establish the
+//! shared key `K`, an application can bootstrap a mutually-authenticated
+//! transport layer without a second round trip by expanding `K` with
+//! HKDF-SHA256 into the identity/key shape RFC 4279 TLS-PSK cipher suites
+//! expect, scoped to a caller-supplied context label.
+
+use hkdf::Hkdf;
+use sha2::Sha256;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use super::{bytes_to_hex, hex_to_bytes, SrpError};
+
+/// A PSK identity/key pair derived from an SRP session key. `key` is
+/// zeroized on drop; `identity` is a public hint, safe to send in the clear.
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SrpPsk {
+    /// Stable PSK identity hint (uppercase hex).
+    #[zeroize(skip)]
+    pub identity: String,
+    /// Derived pre-shared key (uppercase hex).
+    pub key: String,
+}
+
+/// Derive a PSK identity/key pair from a completed SRP session.
+///
+/// Runs HKDF-SHA256 over `session_key`, with `label` as a domain-separating
+/// context string, to independently expand a PSK identity hint and a
+/// `length`-byte key.
+///
+/// # Arguments
+/// * `session_key` - The hex session key (`K`) from `SrpSession`
+/// * `label` - Context label scoping this PSK to its intended use (e.g. the
+///   transport protocol name); callers deriving more than one PSK from the
+///   same session must use distinct labels
+/// * `length` - Desired key length in bytes
+///
+/// # Returns
+/// `SrpPsk` with a 16-byte identity hint and a `length`-byte key, both as
+/// uppercase hex
+pub fn srp_export_psk(session_key: &str, label: &str, length: usize) -> Result<SrpPsk, SrpError> {
+    let ikm = hex_to_bytes(session_key)?;
+    let hkdf = Hkdf::<Sha256>::new(None, &ikm);
+
+    let mut key = Zeroizing::new(vec![0u8; length]);
+    hkdf.expand(format!("{label}:key").as_bytes(), &mut key)
+        .map_err(|e| SrpError::InvalidParameter(format!("HKDF key expansion failed: {}", e)))?;
+
+    let mut identity = [0u8; 16];
+    hkdf.expand(format!("{label}:identity").as_bytes(), &mut identity)
+        .map_err(|e| SrpError::InvalidParameter(format!("HKDF identity expansion failed: {}", e)))?;
+
+    Ok(SrpPsk {
+        identity: bytes_to_hex(&identity),
+        key: bytes_to_hex(&key),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_psk_deterministic() {
+        let session_key = "AD713F5D8F520B7B9413CDD9EF6D9B5FE37F23A9B62C5E2B90D2291F8C3A9E6F";
+
+        let psk1 = srp_export_psk(session_key, "tls13-psk", 32).unwrap();
+        let psk2 = srp_export_psk(session_key, "tls13-psk", 32).unwrap();
+
+        assert_eq!(psk1.identity, psk2.identity);
+        assert_eq!(psk1.key, psk2.key);
+        assert_eq!(hex_to_bytes(&psk1.key).unwrap().len(), 32);
+        assert_eq!(hex_to_bytes(&psk1.identity).unwrap().len(), 16);
+    }
+
+    #[test]
+    fn test_export_psk_respects_length() {
+        let session_key = "AD713F5D8F520B7B9413CDD9EF6D9B5FE37F23A9B62C5E2B90D2291F8C3A9E6F";
+
+        let psk = srp_export_psk(session_key, "label", 48).unwrap();
+        assert_eq!(hex_to_bytes(&psk.key).unwrap().len(), 48);
+    }
+
+    #[test]
+    fn test_export_psk_differs_by_label() {
+        let session_key = "AD713F5D8F520B7B9413CDD9EF6D9B5FE37F23A9B62C5E2B90D2291F8C3A9E6F";
+
+        let psk_a = srp_export_psk(session_key, "label-a", 32).unwrap();
+        let psk_b = srp_export_psk(session_key, "label-b", 32).unwrap();
+
+        assert_ne!(psk_a.key, psk_b.key);
+        assert_ne!(psk_a.identity, psk_b.identity);
+    }
+
+    #[test]
+    fn test_export_psk_identity_and_key_are_independent() {
+        let session_key = "AD713F5D8F520B7B9413CDD9EF6D9B5FE37F23A9B62C5E2B90D2291F8C3A9E6F";
+
+        let psk = srp_export_psk(session_key, "label", 32).unwrap();
+        assert_ne!(psk.identity, psk.key[..32]);
+    }
+
+    #[test]
+    fn test_export_psk_rejects_invalid_hex() {
+        assert!(srp_export_psk("not-hex", "label", 32).is_err());
+    }
+}
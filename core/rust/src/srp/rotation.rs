@@ -0,0 +1,182 @@
+//! Atomic master-password rotation.
+//!
+//! Changing the master password otherwise means orchestrating five separate
+//! calls (generate a salt, hash the new password, derive the new private
+//! key and verifier, then separately re-wrap the vault key) and risking a
+//! partially-applied rotation if a client crashes or a request fails
+//! partway through. [`srp_rotate_credentials`] bundles all of it into one
+//! call: it unwraps the vault's symmetric key with the caller's old
+//! password-derived key, derives a fresh salt/password hash/private
+//! key/verifier for the new password, and re-wraps the vault key under the
+//! new password hash - see [`super::keywrap`] for the wrapping format.
+
+use serde::{Deserialize, Serialize};
+
+use super::keywrap::{unwrap_key, wrap_key};
+use super::{argon2_hash_password, hex_to_bytes, srp_derive_private_key, srp_derive_verifier, srp_generate_salt};
+use super::{DefaultHash, SrpError, SrpGroup};
+
+/// Input for [`srp_rotate_credentials`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct SrpRotationInput {
+    /// Hex-encoded Argon2id key derived from the user's OLD password - the
+    /// key that currently wraps `wrapped_vault_key`.
+    pub old_password_derived_key: String,
+    /// The vault's symmetric encryption key, wrapped under
+    /// `old_password_derived_key` (hex `nonce || ciphertext`, see
+    /// [`super::keywrap::wrap_key`]).
+    pub wrapped_vault_key: String,
+    /// The user's new, raw password.
+    pub new_password: String,
+    /// SRP salt the account's current private key/verifier were derived
+    /// under. Rotation always generates a fresh salt (see
+    /// [`SrpRotationOutput::salt`]), so this isn't consumed by the
+    /// computation - it's validated as hex so a corrupted or stale request
+    /// fails fast rather than silently rotating the wrong account's keys.
+    pub current_salt: String,
+    /// User identity, as passed to `srp_derive_private_key`.
+    pub identity: String,
+    /// RFC 5054 group the new verifier should be computed against.
+    #[serde(default)]
+    pub group: SrpGroup,
+}
+
+/// Output of [`srp_rotate_credentials`].
+#[derive(Debug, Clone, Serialize)]
+pub struct SrpRotationOutput {
+    /// Freshly generated SRP salt for the new password.
+    pub salt: String,
+    /// New Argon2id-derived password hash (hex), computed under `salt`.
+    pub password_hash: String,
+    /// New SRP private key `x`, derived from `password_hash`.
+    pub private_key: String,
+    /// New SRP verifier `v = g^x mod N`.
+    pub verifier: String,
+    /// The vault's symmetric key, re-wrapped under `password_hash` (hex
+    /// `nonce || ciphertext`) - upload this in place of the caller's
+    /// previous wrapped-key blob.
+    pub wrapped_vault_key: String,
+}
+
+/// Rotates a user's master password: re-derives the SRP salt/password
+/// hash/private key/verifier for `input.new_password`, and re-wraps the
+/// vault's symmetric key under the new password hash.
+///
+/// # Arguments
+/// * `input` - Old wrapping key/wrapped vault key plus the new password and
+///   SRP identity - see [`SrpRotationInput`]
+///
+/// # Returns
+/// [`SrpRotationOutput`] with everything a client needs to upload a
+/// complete rotation in a single request.
+pub fn srp_rotate_credentials(input: SrpRotationInput) -> Result<SrpRotationOutput, SrpError> {
+    // Validated for shape only - see the `current_salt` doc comment above.
+    hex_to_bytes(&input.current_salt)?;
+
+    let old_key = hex_to_bytes(&input.old_password_derived_key)?;
+    let vault_key = unwrap_key(&old_key, &input.wrapped_vault_key)?;
+
+    let salt = srp_generate_salt();
+    let password_hash = argon2_hash_password(&input.new_password, &salt)?;
+    let private_key = srp_derive_private_key::<DefaultHash>(&salt, &input.identity, &password_hash)?;
+    let verifier = srp_derive_verifier(&private_key, input.group)?;
+
+    let new_key = hex_to_bytes(&password_hash)?;
+    let wrapped_vault_key = wrap_key(&new_key, &vault_key)?;
+
+    Ok(SrpRotationOutput { salt, password_hash, private_key, verifier, wrapped_vault_key })
+}
+
+/// JSON-in/JSON-out wrapper around [`srp_rotate_credentials`] for the
+/// UniFFI boundary.
+pub fn srp_rotate_credentials_json(input_json: &str) -> Result<String, SrpError> {
+    let input: SrpRotationInput = serde_json::from_str(input_json)
+        .map_err(|e| SrpError::InvalidParameter(format!("invalid rotation request: {e}")))?;
+    let output = srp_rotate_credentials(input)?;
+    serde_json::to_string(&output)
+        .map_err(|e| SrpError::InvalidParameter(format!("failed to serialize rotation response: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::srp::keywrap::wrap_key as wrap_vault_key;
+
+    fn rotation_input(old_key: &[u8], vault_key: &[u8]) -> SrpRotationInput {
+        SrpRotationInput {
+            old_password_derived_key: crate::srp::bytes_to_hex(old_key),
+            wrapped_vault_key: wrap_vault_key(old_key, vault_key).unwrap(),
+            new_password: "new-correct-horse-battery-staple".to_string(),
+            current_salt: srp_generate_salt(),
+            identity: "alice".to_string(),
+            group: SrpGroup::G2048,
+        }
+    }
+
+    #[test]
+    fn test_rotate_credentials_round_trip_unwraps_with_new_key() {
+        let old_key = [0x42u8; 32];
+        let vault_key = b"this-is-the-vault-symmetric-key";
+
+        let output = srp_rotate_credentials(rotation_input(&old_key, vault_key)).unwrap();
+
+        let new_key = hex_to_bytes(&output.password_hash).unwrap();
+        let recovered = unwrap_key(&new_key, &output.wrapped_vault_key).unwrap();
+        assert_eq!(recovered, vault_key);
+    }
+
+    #[test]
+    fn test_rotate_credentials_produces_consistent_verifier() {
+        let old_key = [0x42u8; 32];
+        let vault_key = b"this-is-the-vault-symmetric-key";
+
+        let output = srp_rotate_credentials(rotation_input(&old_key, vault_key)).unwrap();
+
+        let expected_verifier = srp_derive_verifier(&output.private_key, SrpGroup::G2048).unwrap();
+        assert_eq!(output.verifier, expected_verifier);
+    }
+
+    #[test]
+    fn test_rotate_credentials_fails_with_wrong_old_key() {
+        let old_key = [0x42u8; 32];
+        let wrong_key = [0x99u8; 32];
+        let vault_key = b"this-is-the-vault-symmetric-key";
+
+        let mut input = rotation_input(&old_key, vault_key);
+        input.old_password_derived_key = crate::srp::bytes_to_hex(&wrong_key);
+
+        assert!(srp_rotate_credentials(input).is_err());
+    }
+
+    #[test]
+    fn test_rotate_credentials_fails_with_invalid_current_salt() {
+        let old_key = [0x42u8; 32];
+        let vault_key = b"this-is-the-vault-symmetric-key";
+
+        let mut input = rotation_input(&old_key, vault_key);
+        input.current_salt = "not-hex".to_string();
+
+        assert!(srp_rotate_credentials(input).is_err());
+    }
+
+    #[test]
+    fn test_rotate_credentials_json_round_trip() {
+        let old_key = [0x42u8; 32];
+        let vault_key = b"this-is-the-vault-symmetric-key";
+        let input = rotation_input(&old_key, vault_key);
+        let input_json = serde_json::to_string(&serde_json::json!({
+            "old_password_derived_key": input.old_password_derived_key,
+            "wrapped_vault_key": input.wrapped_vault_key,
+            "new_password": input.new_password,
+            "current_salt": input.current_salt,
+            "identity": input.identity,
+        }))
+        .unwrap();
+
+        let output_json = srp_rotate_credentials_json(&input_json).unwrap();
+        let output: serde_json::Value = serde_json::from_str(&output_json).unwrap();
+
+        assert!(output["salt"].is_string());
+        assert!(output["wrapped_vault_key"].is_string());
+    }
+}
@@ -47,6 +47,17 @@ pub fn merge_vaults_json(input_json: String) -> Result<String, VaultError> {
     crate::vault_merge::merge_vaults_json(&input_json)
 }
 
+/// Merge local and server vaults with tombstone-aware conflict resolution,
+/// so a stale edit can't resurrect an item another device already deleted.
+///
+/// Same input/output JSON shape as [`merge_vaults_json`]; see
+/// [`crate::vault_merge::merge_vaults_tombstone_aware`] for the conflict
+/// resolution rules.
+#[uniffi::export]
+pub fn merge_vaults_tombstone_aware_json(input_json: String) -> Result<String, VaultError> {
+    crate::vault_merge::merge_vaults_tombstone_aware_json(&input_json)
+}
+
 /// Prune expired items from trash (items with DeletedAt older than retention_days).
 ///
 /// # Arguments
@@ -118,7 +129,10 @@ pub fn extract_root_domain(domain: String) -> String {
 // SRP (Secure Remote Password) Functions
 // ═══════════════════════════════════════════════════════════════════════════════
 
-pub use crate::srp::{SrpEphemeral, SrpSession, SrpError};
+pub use crate::srp::{
+    KdfAlgorithm, SrpClientHandshake, SrpEphemeral, SrpError, SrpGroup, SrpKeyDerivation,
+    SrpPsk, SrpServerHandshake, SrpSession,
+};
 
 /// Derive a key from a password using Argon2Id.
 ///
@@ -139,6 +153,36 @@ pub fn argon2_hash_password(password: String, salt: String) -> Result<String, Sr
     crate::srp::argon2_hash_password(&password, &salt)
 }
 
+/// Hash a password with a chosen KDF, returning a PHC-format string that
+/// records the algorithm, its parameters, and the salt.
+///
+/// # Arguments
+/// * `algorithm` - KDF and parameters to hash with
+/// * `password` - The password to hash
+/// * `salt` - Salt as a string (will be UTF-8 encoded)
+///
+/// # Returns
+/// PHC-format string suitable for storage (e.g. `$argon2id$v=19$m=19456,t=2,p=1$<salt>$<hash>`)
+#[uniffi::export]
+pub fn kdf_hash_password(algorithm: KdfAlgorithm, password: String, salt: String) -> Result<String, SrpError> {
+    crate::srp::kdf_hash_password(algorithm, &password, &salt)
+}
+
+/// Verify a password against a PHC-format string previously returned by
+/// `kdf_hash_password`, re-deriving the hash with the algorithm and
+/// parameters recorded in the string.
+///
+/// # Arguments
+/// * `password` - The password to verify
+/// * `phc_string` - PHC-format string from `kdf_hash_password`
+///
+/// # Returns
+/// `true` if `password` matches, `false` otherwise
+#[uniffi::export]
+pub fn kdf_verify_password(password: String, phc_string: String) -> Result<bool, SrpError> {
+    crate::srp::kdf_verify_password(&password, &phc_string)
+}
+
 /// Generate a cryptographic salt for SRP.
 /// Returns a 32-byte random salt as an uppercase hex string.
 #[uniffi::export]
@@ -161,26 +205,52 @@ pub fn srp_derive_private_key(
     identity: String,
     password_hash: String,
 ) -> Result<String, SrpError> {
-    crate::srp::srp_derive_private_key(&salt, &identity, &password_hash)
+    crate::srp::srp_derive_private_key::<crate::srp::DefaultHash>(&salt, &identity, &password_hash)
+}
+
+/// Derive the SRP private key (x), stretching the raw password through a
+/// memory-hard KDF (Argon2id, scrypt, or PBKDF2-HMAC-SHA256) first instead
+/// of requiring the caller to pre-hash it.
+///
+/// # Arguments
+/// * `salt` - Salt as uppercase hex string (reused as the KDF's salt)
+/// * `identity` - User identity (username or SRP identity GUID)
+/// * `password` - The user's raw password
+/// * `algorithm` - KDF and parameters to stretch the password with
+///
+/// # Returns
+/// Private key as uppercase hex string
+#[uniffi::export]
+pub fn srp_derive_private_key_with_kdf(
+    salt: String,
+    identity: String,
+    password: String,
+    algorithm: KdfAlgorithm,
+) -> Result<String, SrpError> {
+    crate::srp::srp_derive_private_key_with_kdf::<crate::srp::DefaultHash>(
+        &salt, &identity, &password, algorithm,
+    )
 }
 
 /// Derive the SRP verifier (v) from a private key.
 ///
 /// # Arguments
 /// * `private_key` - Private key as uppercase hex string
+/// * `group` - RFC 5054 group to derive the verifier in (use the same group
+///   for every later call for this account)
 ///
 /// # Returns
 /// Verifier as uppercase hex string (for registration)
 #[uniffi::export]
-pub fn srp_derive_verifier(private_key: String) -> Result<String, SrpError> {
-    crate::srp::srp_derive_verifier(&private_key)
+pub fn srp_derive_verifier(private_key: String, group: SrpGroup) -> Result<String, SrpError> {
+    crate::srp::srp_derive_verifier(&private_key, group)
 }
 
 /// Generate a client ephemeral key pair.
 /// Returns a pair of public (A) and secret (a) values as uppercase hex strings.
 #[uniffi::export]
-pub fn srp_generate_ephemeral() -> SrpEphemeral {
-    crate::srp::srp_generate_ephemeral()
+pub fn srp_generate_ephemeral(group: SrpGroup) -> SrpEphemeral {
+    crate::srp::srp_generate_ephemeral::<crate::srp::DefaultHash>(group)
 }
 
 /// Derive the client session from server response.
@@ -191,6 +261,9 @@ pub fn srp_generate_ephemeral() -> SrpEphemeral {
 /// * `salt` - Salt as hex string
 /// * `identity` - User identity (username or SRP identity GUID)
 /// * `private_key` - Private key (x) as hex string
+/// * `group` - RFC 5054 group this account's verifier was derived in
+/// * `mode` - How to derive the session key from the shared secret; must
+///   match the server's for this session
 ///
 /// # Returns
 /// Session containing proof and key as uppercase hex strings
@@ -201,20 +274,25 @@ pub fn srp_derive_session(
     salt: String,
     identity: String,
     private_key: String,
+    group: SrpGroup,
+    mode: SrpKeyDerivation,
 ) -> Result<SrpSession, SrpError> {
-    crate::srp::srp_derive_session(&client_secret, &server_public, &salt, &identity, &private_key)
+    crate::srp::srp_derive_session::<crate::srp::DefaultHash>(
+        &client_secret, &server_public, &salt, &identity, &private_key, group, mode,
+    )
 }
 
 /// Generate a server ephemeral key pair.
 ///
 /// # Arguments
 /// * `verifier` - Password verifier (v) as hex string
+/// * `group` - RFC 5054 group this account's verifier was derived in
 ///
 /// # Returns
 /// Ephemeral containing public (B) and secret (b) as uppercase hex strings
 #[uniffi::export]
-pub fn srp_generate_ephemeral_server(verifier: String) -> Result<SrpEphemeral, SrpError> {
-    crate::srp::srp_generate_ephemeral_server(&verifier)
+pub fn srp_generate_ephemeral_server(verifier: String, group: SrpGroup) -> Result<SrpEphemeral, SrpError> {
+    crate::srp::srp_generate_ephemeral_server::<crate::srp::DefaultHash>(&verifier, group)
 }
 
 /// Derive and verify the server session from client response.
@@ -226,6 +304,9 @@ pub fn srp_generate_ephemeral_server(verifier: String) -> Result<SrpEphemeral, S
 /// * `identity` - User identity (not used in calculation, for API compatibility)
 /// * `verifier` - Password verifier (v) as hex string
 /// * `client_proof` - Client proof (M1) as hex string
+/// * `group` - RFC 5054 group this account's verifier was derived in
+/// * `mode` - How to derive the session key from the shared secret; must
+///   match the client's for this session
 ///
 /// # Returns
 /// Session with server proof and key if client proof is valid, None otherwise
@@ -237,17 +318,191 @@ pub fn srp_derive_session_server(
     identity: String,
     verifier: String,
     client_proof: String,
+    group: SrpGroup,
+    mode: SrpKeyDerivation,
 ) -> Result<Option<SrpSession>, SrpError> {
-    crate::srp::srp_derive_session_server(
+    crate::srp::srp_derive_session_server::<crate::srp::DefaultHash>(
         &server_secret,
         &client_public,
         &salt,
         &identity,
         &verifier,
         &client_proof,
+        group,
+        mode,
     )
 }
 
+/// Derive a TLS-PSK identity/key pair from a completed SRP session's key.
+///
+/// # Arguments
+/// * `session_key` - The hex session key (`K`) from `SrpSession`
+/// * `label` - Context label scoping this PSK to its intended use; callers
+///   deriving more than one PSK from the same session must use distinct labels
+/// * `length` - Desired key length in bytes
+///
+/// # Returns
+/// `SrpPsk` with a 16-byte identity hint and a `length`-byte key, both as
+/// uppercase hex
+#[uniffi::export]
+pub fn srp_export_psk(session_key: String, label: String, length: u32) -> Result<SrpPsk, SrpError> {
+    crate::srp::srp_export_psk(&session_key, &label, length as usize)
+}
+
+/// Rotate a user's master password in a single call: re-derives the SRP
+/// salt/password hash/private key/verifier for the new password and
+/// re-wraps the vault's symmetric key under the new password hash.
+///
+/// # Arguments
+/// * `input_json` - JSON string with format:
+///   ```json
+///   {
+///     "old_password_derived_key": "...",
+///     "wrapped_vault_key": "...",
+///     "new_password": "...",
+///     "current_salt": "...",
+///     "identity": "user@example.com",
+///     "group": "g2048"
+///   }
+///   ```
+///
+/// # Returns
+/// JSON string with format:
+///   ```json
+///   {
+///     "salt": "...",
+///     "password_hash": "...",
+///     "private_key": "...",
+///     "verifier": "...",
+///     "wrapped_vault_key": "..."
+///   }
+///   ```
+#[uniffi::export]
+pub fn srp_rotate_credentials_json(input_json: String) -> Result<String, SrpError> {
+    crate::srp::srp_rotate_credentials_json(&input_json)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// TOTP (Time-Based One-Time Password) Functions
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Generate the current RFC 6238 TOTP code for a stored secret.
+///
+/// # Arguments
+/// * `input_json` - JSON string with format:
+///   ```json
+///   {
+///     "secret": "JBSWY3DPEHPK3PXP",
+///     "period": 30,
+///     "digits": 6,
+///     "algorithm": "SHA1",
+///     "unix_time": 1700000000
+///   }
+///   ```
+///   `secret` may also be a full `otpauth://` URI, in which case its own
+///   `secret`/`period`/`digits`/`algorithm` query parameters are used instead.
+///
+/// # Returns
+/// JSON string with format:
+///   ```json
+///   {
+///     "code": "123456",
+///     "seconds_remaining": 12
+///   }
+///   ```
+#[uniffi::export]
+pub fn generate_totp_json(input_json: String) -> Result<String, VaultError> {
+    crate::totp::generate_totp_json(&input_json)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// Emergency Access Functions
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Seal a vault key into an emergency-access grant for a trusted contact.
+///
+/// # Arguments
+/// * `input_json` - JSON string with format:
+///   ```json
+///   {
+///     "grantee_public_key": "...",
+///     "key_algorithm": "X25519",
+///     "vault_key": "...",
+///     "grantee_id": "contact-123",
+///     "wait_days": 7,
+///     "created_at": 1700000000
+///   }
+///   ```
+///
+/// # Returns
+/// JSON string with format:
+///   ```json
+///   {
+///     "sealed_vault_key": "...",
+///     "grantee_id": "contact-123",
+///     "wait_days": 7,
+///     "created_at": 1700000000
+///   }
+///   ```
+#[uniffi::export]
+pub fn emergency_grant_json(input_json: String) -> Result<String, VaultError> {
+    crate::emergency_access::emergency_grant_json(&input_json)
+}
+
+/// Check whether an emergency-access grant is ready for its grantee to
+/// unseal: auto-approved once the wait period elapses, unless the grantor
+/// has explicitly approved or rejected it.
+///
+/// # Arguments
+/// * `input_json` - JSON string with format:
+///   ```json
+///   {
+///     "created_at": 1700000000,
+///     "wait_days": 7,
+///     "decision": "pending",
+///     "current_time": 1700086400
+///   }
+///   ```
+///
+/// # Returns
+/// JSON string with format:
+///   ```json
+///   {
+///     "ready": false,
+///     "seconds_remaining": 518400
+///   }
+///   ```
+#[uniffi::export]
+pub fn emergency_access_ready_json(input_json: String) -> Result<String, VaultError> {
+    crate::emergency_access::emergency_access_ready_json(&input_json)
+}
+
+/// Recover a vault key from a ready emergency-access grant. Callers must
+/// check `emergency_access_ready_json` first - this doesn't re-check the
+/// wait period or grantor decision itself.
+///
+/// # Arguments
+/// * `input_json` - JSON string with format:
+///   ```json
+///   {
+///     "sealed_vault_key": "...",
+///     "key_algorithm": "X25519",
+///     "grantee_private_key": "..."
+///   }
+///   ```
+///
+/// # Returns
+/// JSON string with format:
+///   ```json
+///   {
+///     "vault_key": "..."
+///   }
+///   ```
+#[uniffi::export]
+pub fn emergency_unseal_vault_key_json(input_json: String) -> Result<String, VaultError> {
+    crate::emergency_access::emergency_unseal_vault_key_json(&input_json)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -301,4 +556,22 @@ mod tests {
         assert_eq!(extract_root_domain("www.example.com".to_string()), "example.com");
         assert_eq!(extract_root_domain("github.com".to_string()), "github.com");
     }
+
+    #[test]
+    fn test_generate_totp_json() {
+        let input = r#"{
+            "secret": "GEZDGNBVGY3TQOJQ",
+            "period": 30,
+            "digits": 6,
+            "algorithm": "SHA1",
+            "unix_time": 1700000000
+        }"#;
+
+        let result = generate_totp_json(input.to_string());
+        assert!(result.is_ok());
+
+        let output: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+        assert_eq!(output["code"].as_str().unwrap().len(), 6);
+        assert!(output["seconds_remaining"].as_u64().unwrap() <= 30);
+    }
 }
@@ -2,6 +2,35 @@
 //!
 //! This module provides the core merge functionality that works on JSON table data.
 //! It generates SQL statements that clients can execute directly on their local database.
+//!
+//! Plain LWW ([`merge_vaults`]) resolves every conflict by comparing `UpdatedAt`
+//! alone, which lets a stale edit on one device resurrect an item another
+//! device already trashed. [`merge_vaults_tombstone_aware`] is an additive
+//! second mode for callers who want deletion treated as a first-class,
+//! causally-preserved tombstone instead: see its doc comment for the rules.
+//!
+//! Within plain LWW, a winning `IsDeleted = true` record is deleted outright
+//! (`DELETE FROM ... WHERE Id = ?`) rather than written back as an `UPDATE`,
+//! and a server-only record that is already a tombstone is never inserted in
+//! the first place - see [`MergeStats::records_deleted`].
+//!
+//! When a caller also supplies `MergeInput::base_tables` (the last-synced
+//! snapshot), a record present in base, local, and server is merged
+//! field-by-field instead of picking one side's row wholesale: see
+//! [`merge_record_three_way`] for the column-level rules.
+//!
+//! Without a base snapshot, a table can still opt specific columns out of
+//! whole-row LWW via [`types::TableConfig::lww_columns`]: each registered
+//! column is resolved against its own companion timestamp column instead of
+//! the record's `UpdatedAt`, so concurrent edits to different registered
+//! columns both survive - see [`merge_record_field_lww`].
+//!
+//! A server record's JSON map keys end up interpolated directly into
+//! generated SQL, so every column name is checked against its table's
+//! [`types::TableConfig::allowed_columns`] schema before code generation:
+//! anything not listed there is dropped from the statement rather than
+//! emitted, and reported back via [`MergeStats::rejected_identifiers`] - see
+//! [`filter_column`].
 
 mod types;
 
@@ -10,7 +39,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::error::VaultResult;
-use types::SYNCABLE_TABLES;
+use types::{TableConfig, SYNCABLE_TABLES};
 pub use types::SYNCABLE_TABLE_NAMES;
 
 /// A record is a map of column names to JSON values.
@@ -32,6 +61,13 @@ pub struct MergeInput {
     pub local_tables: Vec<TableData>,
     /// Tables from the server database
     pub server_tables: Vec<TableData>,
+    /// Tables as they were at the last successful sync (the common ancestor).
+    /// When a record's table and key are present here, `merge_vaults` merges
+    /// it field-by-field instead of picking one side's row wholesale; when
+    /// empty (the default) or a given record has no base row, it falls back
+    /// to plain two-way LWW.
+    #[serde(default)]
+    pub base_tables: Vec<TableData>,
 }
 
 /// A SQL statement with its parameter values.
@@ -43,6 +79,19 @@ pub struct SqlStatement {
     pub params: Vec<serde_json::Value>,
 }
 
+/// A record column that was dropped from the generated SQL because it is
+/// not in its table's [`types::TableConfig::allowed_columns`] schema - either
+/// a malformed payload or a server record carrying a column this client's
+/// schema doesn't know about.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct RejectedIdentifier {
+    /// Table the offending record belongs to
+    pub table: String,
+    /// The column name that was rejected
+    pub column: String,
+}
+
 /// Statistics about what was merged.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
@@ -59,6 +108,18 @@ pub struct MergeStats {
     pub conflicts: u32,
     /// Records inserted from server (server-only records)
     pub records_inserted: u32,
+    /// Number of individual columns where a three-way merge found local and
+    /// server had both changed the same field to different values, so it
+    /// fell back to per-field LWW
+    pub field_conflicts: u32,
+    /// Records removed locally because the winning side had `IsDeleted = true`
+    pub records_deleted: u32,
+    /// Records whose `TableConfig::position_column` rank was reassigned to a
+    /// fresh midpoint value because it collided with another record's rank
+    pub positions_rebalanced: u32,
+    /// Columns dropped from generated SQL because they aren't in their
+    /// table's `TableConfig::allowed_columns` schema
+    pub rejected_identifiers: Vec<RejectedIdentifier>,
 }
 
 /// Output of the merge operation.
@@ -96,12 +157,19 @@ pub fn merge_vaults(input: MergeInput) -> VaultResult<MergeOutput> {
         .map(|t| (t.name.as_str(), t))
         .collect();
 
+    let base_map: HashMap<&str, &TableData> = input
+        .base_tables
+        .iter()
+        .map(|t| (t.name.as_str(), t))
+        .collect();
+
     // Process each syncable table
     for table_config in SYNCABLE_TABLES {
         let table_name = table_config.name;
 
         let local_data = local_map.get(table_name);
         let server_data = server_map.get(table_name);
+        let base_records = base_map.get(table_name).map(|t| t.records.as_slice());
 
         // Skip if table doesn't exist in either database
         let (local_records, server_records) = match (local_data, server_data) {
@@ -114,7 +182,7 @@ pub fn merge_vaults(input: MergeInput) -> VaultResult<MergeOutput> {
             (None, Some(s)) => {
                 // Table only in server - insert all
                 for record in &s.records {
-                    if let Some(stmt) = generate_insert_sql(table_name, record) {
+                    if let Some(stmt) = generate_insert_sql(table_name, record, table_config.allowed_columns, &mut total_stats) {
                         statements.push(stmt);
                         total_stats.records_inserted += 1;
                     }
@@ -127,19 +195,738 @@ pub fn merge_vaults(input: MergeInput) -> VaultResult<MergeOutput> {
 
         // Merge the table and generate SQL statements
         let table_statements = if table_config.uses_composite_key() {
-            merge_table_by_composite_key(
+            merge_table_by_composite_key(local_records, server_records, base_records, table_config, &mut total_stats)
+        } else {
+            merge_table_by_id(local_records, server_records, base_records, table_config, &mut total_stats)
+        };
+
+        statements.extend(table_statements);
+
+        if let Some(position_column) = table_config.position_column {
+            statements.extend(rebalance_positions(
+                table_name,
+                local_records,
+                server_records,
+                position_column,
+                &mut total_stats,
+            ));
+        }
+
+        total_stats.tables_processed += 1;
+    }
+
+    Ok(MergeOutput {
+        success: true,
+        statements,
+        stats: total_stats,
+    })
+}
+
+/// Merge a JSON string input and return JSON string output.
+/// Convenience function for FFI.
+pub fn merge_vaults_json(input_json: &str) -> VaultResult<String> {
+    let input: MergeInput = serde_json::from_str(input_json)?;
+    let output = merge_vaults(input)?;
+    let output_json = serde_json::to_string(&output)?;
+    Ok(output_json)
+}
+
+/// Merge table records by Id using two-way LWW, or field-level three-way
+/// merge for any record that also has a matching `base_records` row.
+///
+/// `table_config.lww_columns` additionally resolves specific columns
+/// independently by their own companion timestamp, for live (non-deleted)
+/// records with no `base_records` match.
+fn merge_table_by_id(
+    local_records: &[Record],
+    server_records: &[Record],
+    base_records: Option<&[Record]>,
+    table_config: &TableConfig,
+    stats: &mut MergeStats,
+) -> Vec<SqlStatement> {
+    let table_name = table_config.name;
+    let lww_columns = table_config.lww_columns;
+    let allowed_columns = table_config.allowed_columns;
+    let mut statements: Vec<SqlStatement> = Vec::new();
+
+    let mut server_map: HashMap<String, &Record> = HashMap::new();
+    for record in server_records {
+        if let Some(id) = get_record_id(record) {
+            server_map.insert(id, record);
+        }
+    }
+
+    let base_map: HashMap<String, &Record> = base_records
+        .unwrap_or(&[])
+        .iter()
+        .filter_map(|r| Some((get_record_id(r)?, r)))
+        .collect();
+
+    for local_record in local_records {
+        let local_id = match get_record_id(local_record) {
+            Some(id) => id,
+            None => continue,
+        };
+
+        if let Some(server_record) = server_map.get(&local_id) {
+            if let Some(base_record) = base_map.get(&local_id) {
+                match merge_record_three_way(
+                    table_name,
+                    local_record,
+                    server_record,
+                    base_record,
+                    &local_id,
+                    allowed_columns,
+                    stats,
+                ) {
+                    Some(stmt) => {
+                        statements.push(stmt);
+                        stats.conflicts += 1;
+                        stats.records_from_server += 1;
+                    }
+                    None => stats.records_from_local += 1,
+                }
+            } else if !lww_columns.is_empty() && !is_deleted(local_record) && !is_deleted(server_record) {
+                match merge_record_field_lww(
+                    table_name,
+                    local_record,
+                    server_record,
+                    lww_columns,
+                    &local_id,
+                    allowed_columns,
+                    stats,
+                ) {
+                    Some(stmt) => {
+                        statements.push(stmt);
+                        stats.conflicts += 1;
+                        stats.records_from_server += 1;
+                    }
+                    None => stats.records_from_local += 1,
+                }
+            } else if get_updated_at(server_record) > get_updated_at(local_record) {
+                stats.conflicts += 1;
+                stats.records_from_server += 1;
+                if is_deleted(server_record) {
+                    stats.records_deleted += 1;
+                    statements.push(generate_delete_sql(table_name, &local_id));
+                } else if let Some(stmt) = generate_update_sql(table_name, server_record, &local_id, allowed_columns, stats) {
+                    statements.push(stmt);
+                }
+            } else {
+                stats.records_from_local += 1;
+            }
+            server_map.remove(&local_id);
+        } else {
+            stats.records_created_locally += 1;
+        }
+    }
+
+    for (_id, server_record) in server_map {
+        // A tombstone that was never seen locally has nothing to delete.
+        if is_deleted(server_record) {
+            continue;
+        }
+        stats.records_inserted += 1;
+        if let Some(stmt) = generate_insert_sql(table_name, server_record, allowed_columns, stats) {
+            statements.push(stmt);
+        }
+    }
+
+    statements
+}
+
+/// Merge table records by composite key using two-way LWW, or field-level
+/// three-way merge for any record that also has a matching `base_records` row.
+///
+/// `table_config.lww_columns` additionally resolves specific columns
+/// independently by their own companion timestamp, for live (non-deleted)
+/// records with no `base_records` match.
+fn merge_table_by_composite_key(
+    local_records: &[Record],
+    server_records: &[Record],
+    base_records: Option<&[Record]>,
+    table_config: &TableConfig,
+    stats: &mut MergeStats,
+) -> Vec<SqlStatement> {
+    let table_name = table_config.name;
+    let key_columns = table_config.composite_key_columns;
+    let lww_columns = table_config.lww_columns;
+    let allowed_columns = table_config.allowed_columns;
+    let mut statements: Vec<SqlStatement> = Vec::new();
+
+    let mut server_map: HashMap<String, &Record> = HashMap::new();
+    for record in server_records {
+        let key = get_composite_key(record, key_columns);
+        if let Some(existing) = server_map.get(&key) {
+            if get_updated_at(record) > get_updated_at(existing) {
+                server_map.insert(key, record);
+            }
+        } else {
+            server_map.insert(key, record);
+        }
+    }
+
+    let mut base_map: HashMap<String, &Record> = HashMap::new();
+    for record in base_records.unwrap_or(&[]) {
+        base_map.insert(get_composite_key(record, key_columns), record);
+    }
+
+    for local_record in local_records {
+        let composite_key = get_composite_key(local_record, key_columns);
+        let local_id = match get_record_id(local_record) {
+            Some(id) => id,
+            None => continue,
+        };
+
+        if let Some(server_record) = server_map.get(&composite_key) {
+            if let Some(base_record) = base_map.get(&composite_key) {
+                match merge_record_three_way(
+                    table_name,
+                    local_record,
+                    server_record,
+                    base_record,
+                    &local_id,
+                    allowed_columns,
+                    stats,
+                ) {
+                    Some(stmt) => {
+                        statements.push(stmt);
+                        stats.conflicts += 1;
+                        stats.records_from_server += 1;
+                    }
+                    None => stats.records_from_local += 1,
+                }
+            } else if !lww_columns.is_empty() && !is_deleted(local_record) && !is_deleted(server_record) {
+                match merge_record_field_lww(
+                    table_name,
+                    local_record,
+                    server_record,
+                    lww_columns,
+                    &local_id,
+                    allowed_columns,
+                    stats,
+                ) {
+                    Some(stmt) => {
+                        statements.push(stmt);
+                        stats.conflicts += 1;
+                        stats.records_from_server += 1;
+                    }
+                    None => stats.records_from_local += 1,
+                }
+            } else if get_updated_at(server_record) > get_updated_at(local_record) {
+                stats.conflicts += 1;
+                stats.records_from_server += 1;
+                if is_deleted(server_record) {
+                    stats.records_deleted += 1;
+                    statements.push(generate_delete_sql_composite(table_name, server_record, key_columns));
+                } else if let Some(stmt) = generate_update_sql(table_name, server_record, &local_id, allowed_columns, stats) {
+                    statements.push(stmt);
+                }
+            } else {
+                stats.records_from_local += 1;
+            }
+            server_map.remove(&composite_key);
+        } else {
+            stats.records_created_locally += 1;
+        }
+    }
+
+    for (_key, server_record) in server_map {
+        // A tombstone that was never seen locally has nothing to delete.
+        if is_deleted(server_record) {
+            continue;
+        }
+        stats.records_inserted += 1;
+        if let Some(stmt) = generate_insert_sql(table_name, server_record, allowed_columns, stats) {
+            statements.push(stmt);
+        }
+    }
+
+    statements
+}
+
+/// After merging a position-ordered table (see
+/// [`types::TableConfig::position_column`]), reassign ranks for any records
+/// that ended up sharing the exact same fractional-index string so sibling
+/// order survives the merge instead of getting scrambled.
+///
+/// Builds the table's resulting (Id, rank) pairs the same way the ordinary
+/// two-way LWW branch would - the side with the newer `UpdatedAt` wins the
+/// whole record, rank included - then walks them in rank order. Within each
+/// run of records sharing one rank, the first is left untouched and every
+/// following one is moved to a fresh midpoint rank strictly between the
+/// previous rank and the next distinct one, so the emitted statements are
+/// already in the final sorted-rank order.
+fn rebalance_positions(
+    table_name: &str,
+    local_records: &[Record],
+    server_records: &[Record],
+    position_column: &str,
+    stats: &mut MergeStats,
+) -> Vec<SqlStatement> {
+    let mut server_map: HashMap<String, &Record> = HashMap::new();
+    for record in server_records {
+        if let Some(id) = get_record_id(record) {
+            server_map.insert(id, record);
+        }
+    }
+
+    let mut final_ranks: HashMap<String, String> = HashMap::new();
+    for local_record in local_records {
+        let id = match get_record_id(local_record) {
+            Some(id) => id,
+            None => continue,
+        };
+
+        let winner: &Record = match server_map.remove(&id) {
+            Some(server_record) if get_updated_at(server_record) > get_updated_at(local_record) => server_record,
+            _ => local_record,
+        };
+        if let Some(rank) = winner.get(position_column).and_then(|v| v.as_str()) {
+            final_ranks.insert(id, rank.to_string());
+        }
+    }
+    for (id, server_record) in server_map {
+        if let Some(rank) = server_record.get(position_column).and_then(|v| v.as_str()) {
+            final_ranks.insert(id, rank.to_string());
+        }
+    }
+
+    let mut sorted: Vec<(String, String)> = final_ranks.into_iter().collect();
+    sorted.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut statements = Vec::new();
+    let mut i = 0;
+    while i < sorted.len() {
+        let rank_value = sorted[i].1.clone();
+        let mut j = i + 1;
+        while j < sorted.len() && sorted[j].1 == rank_value {
+            j += 1;
+        }
+
+        if j - i > 1 {
+            let upper = sorted.get(j).map(|(_, rank)| rank.clone());
+            let mut previous = rank_value;
+            for (id, _) in &sorted[i + 1..j] {
+                let new_rank = midpoint_rank(Some(previous.as_str()), upper.as_deref());
+                stats.positions_rebalanced += 1;
+                statements.push(SqlStatement {
+                    sql: format!("UPDATE {} SET {} = ? WHERE Id = ?", table_name, position_column),
+                    params: vec![serde_json::json!(new_rank), serde_json::json!(id)],
+                });
+                previous = new_rank;
+            }
+        }
+
+        i = j;
+    }
+
+    statements
+}
+
+/// Base-62 alphabet for fractional-index rank strings: lexicographic string
+/// order must match numeric order, so digits sort before uppercase which
+/// sorts before lowercase.
+const RANK_ALPHABET: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+fn rank_digit_value(c: u8) -> u32 {
+    RANK_ALPHABET.iter().position(|&b| b == c).unwrap_or(0) as u32
+}
+
+fn rank_digit_char(v: u32) -> u8 {
+    RANK_ALPHABET[v as usize % RANK_ALPHABET.len()]
+}
+
+/// Generate a rank string that sorts strictly between `lower` and `upper`.
+/// `lower = None` means "before everything", `upper = None` means "after
+/// everything". Each rank string is treated as a base-62 fraction with
+/// implicit trailing zero digits, so this always has room: it pads both
+/// sides to one digit longer than the longer input, averages them with
+/// base-62 big-number arithmetic, and strips trailing zero digits from the
+/// result.
+fn midpoint_rank(lower: Option<&str>, upper: Option<&str>) -> String {
+    let base = RANK_ALPHABET.len() as u32;
+    let lower_digits: Vec<u32> = lower.unwrap_or("").bytes().map(rank_digit_value).collect();
+    let upper_digits: Vec<u32> = upper.unwrap_or("").bytes().map(rank_digit_value).collect();
+
+    let len = lower_digits.len().max(upper_digits.len()) + 1;
+
+    let mut lo = lower_digits;
+    lo.resize(len, 0);
+
+    let mut hi = upper_digits;
+    let upper_pad = if upper.is_none() { base - 1 } else { 0 };
+    hi.resize(len, upper_pad);
+
+    // Add lo + hi into a `len + 1`-digit big number (most significant digit
+    // first), then halve it digit by digit to get their average.
+    let mut sum = vec![0u32; len + 1];
+    let mut carry = 0u32;
+    for idx in (0..len).rev() {
+        let total = lo[idx] + hi[idx] + carry;
+        sum[idx + 1] = total % base;
+        carry = total / base;
+    }
+    sum[0] = carry;
+
+    let mut mean = vec![0u32; len + 1];
+    let mut remainder = 0u32;
+    for (idx, digit) in sum.iter().enumerate() {
+        let total = remainder * base + digit;
+        mean[idx] = total / 2;
+        remainder = total % 2;
+    }
+
+    // `mean` has one extra leading digit of headroom from the carry above;
+    // it is always 0 since lo/hi are each < 1.0 as base-62 fractions.
+    let trimmed: Vec<u32> = mean[1..].to_vec();
+    let mut result: String = trimmed.iter().map(|&d| rank_digit_char(d) as char).collect();
+    while result.len() > 1 && result.ends_with(RANK_ALPHABET[0] as char) {
+        result.pop();
+    }
+
+    result
+}
+
+/// Merges one record present on both `local` and `server`, given its
+/// last-synced `base` snapshot: a column changed on only one side takes that
+/// side's value, a column changed on both sides to the same value is
+/// non-conflicting, and a column changed on both to different values falls
+/// back to LWW by `UpdatedAt` (server wins on tie, counted in
+/// `MergeStats::field_conflicts`).
+///
+/// Returns an `UPDATE` containing only the columns whose merged value
+/// differs from the current local row, or `None` if nothing changed.
+fn merge_record_three_way(
+    table_name: &str,
+    local: &Record,
+    server: &Record,
+    base: &Record,
+    id: &str,
+    allowed_columns: &[&str],
+    stats: &mut MergeStats,
+) -> Option<SqlStatement> {
+    let mut columns: Vec<&String> = local.keys().chain(server.keys()).collect();
+    columns.sort();
+    columns.dedup();
+
+    let mut set_columns: Vec<&str> = Vec::new();
+    let mut params: Vec<serde_json::Value> = Vec::new();
+    let mut merged_is_deleted = is_deleted(local);
+
+    for column in columns {
+        // "Id" never changes and "UpdatedAt" is metadata, not content: it
+        // differs from `base` on both sides whenever *any* field changed,
+        // which would otherwise register as its own spurious field conflict
+        // on every three-way merge. It's stamped separately below instead.
+        if column == "Id" || column == "UpdatedAt" {
+            continue;
+        }
+        if !filter_column(table_name, column, allowed_columns, stats) {
+            continue;
+        }
+
+        let base_val = base.get(column);
+        let local_val = local.get(column);
+        let server_val = server.get(column);
+
+        let local_changed = local_val != base_val;
+        let server_changed = server_val != base_val;
+
+        let merged_val = if local_changed && server_changed {
+            if local_val == server_val {
+                local_val
+            } else {
+                stats.field_conflicts += 1;
+                if get_updated_at(server) >= get_updated_at(local) {
+                    server_val
+                } else {
+                    local_val
+                }
+            }
+        } else if server_changed {
+            server_val
+        } else {
+            local_val
+        };
+
+        if column == "IsDeleted" {
+            merged_is_deleted = merged_val.and_then(serde_json::Value::as_bool).unwrap_or(false);
+        }
+
+        if merged_val != local_val {
+            set_columns.push(column.as_str());
+            params.push(merged_val.cloned().unwrap_or(serde_json::Value::Null));
+        }
+    }
+
+    // Stamp UpdatedAt to whichever side is newer, without counting it as a
+    // field conflict or running it through the local/server/base diff above.
+    let newer_side = if get_updated_at(server) >= get_updated_at(local) { server } else { local };
+    if let Some(newer_updated_at) = newer_side.get("UpdatedAt") {
+        if local.get("UpdatedAt") != Some(newer_updated_at) {
+            set_columns.push("UpdatedAt");
+            params.push(newer_updated_at.clone());
+        }
+    }
+
+    if set_columns.is_empty() {
+        return None;
+    }
+
+    // A winning IsDeleted=true is deleted outright, mirroring the two-way LWW
+    // branch in `merge_table_by_id`/`merge_table_by_composite_key` - it's
+    // never written back as an UPDATE.
+    if merged_is_deleted {
+        stats.records_deleted += 1;
+        return Some(generate_delete_sql(table_name, id));
+    }
+
+    let set_clause = set_columns.iter().map(|c| format!("{} = ?", c)).collect::<Vec<_>>().join(", ");
+    params.push(serde_json::json!(id));
+
+    Some(SqlStatement {
+        sql: format!("UPDATE {} SET {} WHERE Id = ?", table_name, set_clause),
+        params,
+    })
+}
+
+/// Merge local and server versions of one record for a table with no
+/// matching `base_records` row, where `lww_columns` (see
+/// [`types::TableConfig::lww_columns`]) names columns that carry their own
+/// companion timestamp. Each such column is resolved by comparing that
+/// companion timestamp directly, local wins ties; every other column still
+/// follows the record-level `UpdatedAt` compare. This is what lets two
+/// devices independently edit different registered columns of the same
+/// record without either edit being discarded.
+fn merge_record_field_lww(
+    table_name: &str,
+    local: &Record,
+    server: &Record,
+    lww_columns: &[(&str, &str)],
+    id: &str,
+    allowed_columns: &[&str],
+    stats: &mut MergeStats,
+) -> Option<SqlStatement> {
+    let record_server_newer = get_updated_at(server) > get_updated_at(local);
+
+    let mut columns: Vec<&String> = local.keys().chain(server.keys()).collect();
+    columns.sort();
+    columns.dedup();
+
+    let mut set_columns: Vec<&str> = Vec::new();
+    let mut params: Vec<serde_json::Value> = Vec::new();
+
+    for column in columns {
+        if column == "Id" || column == "UpdatedAt" {
+            continue;
+        }
+        // A companion timestamp column is written alongside its data column
+        // below, never diffed as a column in its own right.
+        if lww_columns.iter().any(|pair| pair.1 == column.as_str()) {
+            continue;
+        }
+        if !filter_column(table_name, column, allowed_columns, stats) {
+            continue;
+        }
+
+        let local_val = local.get(column);
+        let server_val = server.get(column);
+
+        let companion: Option<(&str, &str)> = lww_columns.iter().find(|pair| pair.0 == column.as_str()).copied();
+        let server_wins = match companion {
+            Some((_, ts_column)) => {
+                if local_val != server_val {
+                    stats.field_conflicts += 1;
+                }
+                get_column_timestamp(server, ts_column) > get_column_timestamp(local, ts_column)
+            }
+            None => record_server_newer,
+        };
+
+        let merged_val = if server_wins { server_val } else { local_val };
+
+        if merged_val != local_val {
+            set_columns.push(column.as_str());
+            params.push(merged_val.cloned().unwrap_or(serde_json::Value::Null));
+
+            if let Some((_, ts_column)) = companion {
+                if let Some(ts_val) = server.get(ts_column) {
+                    set_columns.push(ts_column);
+                    params.push(ts_val.clone());
+                }
+            }
+        }
+    }
+
+    // Stamp UpdatedAt to whichever side is newer overall, same as
+    // `merge_record_three_way` - it's metadata, not a registered column.
+    let newer_side = if record_server_newer { server } else { local };
+    if let Some(newer_updated_at) = newer_side.get("UpdatedAt") {
+        if local.get("UpdatedAt") != Some(newer_updated_at) {
+            set_columns.push("UpdatedAt");
+            params.push(newer_updated_at.clone());
+        }
+    }
+
+    if set_columns.is_empty() {
+        return None;
+    }
+
+    let set_clause = set_columns.iter().map(|c| format!("{} = ?", c)).collect::<Vec<_>>().join(", ");
+    params.push(serde_json::json!(id));
+
+    Some(SqlStatement {
+        sql: format!("UPDATE {} SET {} WHERE Id = ?", table_name, set_clause),
+        params,
+    })
+}
+
+/// Merge local and server vault data with tombstone-aware conflict resolution.
+///
+/// [`merge_vaults`] compares `UpdatedAt` alone, so a stale edit on one device
+/// can resurrect an item another device already deleted. This entry point
+/// instead treats each record as a per-field LWW-register plus a separate
+/// deletion tombstone (`IsDeleted`/`DeletedAt`):
+///
+/// * If one side is deleted and its `DeletedAt` is at or after the other
+///   side's `UpdatedAt`, the deletion wins and the merged record stays deleted.
+/// * Otherwise the newer `UpdatedAt` wins as plain content - a deletion is
+///   only overturned by a content update strictly newer than it, i.e. a
+///   genuine resurrection rather than a stale edit racing the delete.
+///
+/// Tables declared as children of another table in [`types::SYNCABLE_TABLES`]
+/// (FieldValues, Attachments, TotpCodes, Passkeys are children of Items)
+/// cascade their parent's tombstone: once an Item ends up deleted here, its
+/// still-live child rows are force-deleted too, the same way
+/// [`crate::vault_pruner`] cascades a permanent delete to related entities.
+///
+/// Existing callers of [`merge_vaults`]/[`merge_vaults_json`] are unaffected;
+/// this is an additive mode, not a replacement.
+///
+/// # Arguments
+/// * `input` - MergeInput containing local and server table data
+///
+/// # Returns
+/// MergeOutput with SQL statements to execute on local database
+pub fn merge_vaults_tombstone_aware(input: MergeInput) -> VaultResult<MergeOutput> {
+    let mut total_stats = MergeStats::default();
+    let mut statements: Vec<SqlStatement> = Vec::new();
+    // Ids that ended up deleted per table, for cascading to child tables below.
+    let mut deleted_ids: HashMap<&str, HashMap<String, DateTime<Utc>>> = HashMap::new();
+
+    let local_map: HashMap<&str, &TableData> = input
+        .local_tables
+        .iter()
+        .map(|t| (t.name.as_str(), t))
+        .collect();
+
+    let server_map: HashMap<&str, &TableData> = input
+        .server_tables
+        .iter()
+        .map(|t| (t.name.as_str(), t))
+        .collect();
+
+    for table_config in SYNCABLE_TABLES {
+        let table_name = table_config.name;
+
+        let local_data = local_map.get(table_name);
+        let server_data = server_map.get(table_name);
+
+        let (local_records, server_records) = match (local_data, server_data) {
+            (Some(l), None) => {
+                total_stats.records_created_locally += l.records.len() as u32;
+                deleted_ids.insert(table_name, tombstones_from_records(&l.records));
+                continue;
+            }
+            (None, Some(s)) => {
+                for record in &s.records {
+                    if let Some(stmt) = generate_insert_sql(table_name, record, table_config.allowed_columns, &mut total_stats) {
+                        statements.push(stmt);
+                        total_stats.records_inserted += 1;
+                    }
+                }
+                total_stats.tables_processed += 1;
+                deleted_ids.insert(table_name, tombstones_from_records(&s.records));
+                continue;
+            }
+            (None, None) => continue,
+            (Some(l), Some(s)) => (&l.records, &s.records),
+        };
+
+        let (table_statements, table_deleted) = if table_config.uses_composite_key() {
+            merge_table_by_composite_key_tombstone_aware(
+                table_name,
+                local_records,
+                server_records,
+                table_config.composite_key_columns,
+                table_config.allowed_columns,
+                &mut total_stats,
+            )
+        } else {
+            merge_table_by_id_tombstone_aware(
                 table_name,
                 local_records,
                 server_records,
-                table_config.composite_key_columns,
+                table_config.allowed_columns,
                 &mut total_stats,
             )
-        } else {
-            merge_table_by_id(table_name, local_records, server_records, &mut total_stats)
         };
 
         statements.extend(table_statements);
+
+        if let Some(position_column) = table_config.position_column {
+            statements.extend(rebalance_positions(
+                table_name,
+                local_records,
+                server_records,
+                position_column,
+                &mut total_stats,
+            ));
+        }
+
         total_stats.tables_processed += 1;
+        deleted_ids.insert(table_name, table_deleted);
+    }
+
+    // Cascade: a table declared as a child of another in SYNCABLE_TABLES gets
+    // its still-live rows force-deleted once the parent id they reference
+    // ended up deleted above, mirroring vault_pruner's
+    // "UPDATE ... SET IsDeleted = 1 ... WHERE ParentId = ? AND IsDeleted = 0".
+    for table_config in SYNCABLE_TABLES {
+        let (parent_table, parent_id_column) =
+            match (table_config.parent_table, table_config.parent_id_column) {
+                (Some(t), Some(c)) => (t, c),
+                _ => continue,
+            };
+        let parents = match deleted_ids.get(parent_table) {
+            Some(p) => p,
+            None => continue,
+        };
+
+        let child_records: Vec<&Record> = local_map
+            .get(table_config.name)
+            .into_iter()
+            .chain(server_map.get(table_config.name))
+            .flat_map(|t| t.records.iter())
+            .collect();
+
+        for (parent_id, deleted_at) in parents {
+            let has_live_children = child_records.iter().any(|r| {
+                r.get(parent_id_column).and_then(|v| v.as_str()) == Some(parent_id.as_str())
+                    && !is_deleted(r)
+            });
+            if has_live_children {
+                statements.push(SqlStatement {
+                    sql: format!(
+                        "UPDATE {} SET IsDeleted = 1, DeletedAt = ? WHERE {} = ? AND IsDeleted = 0",
+                        table_config.name, parent_id_column
+                    ),
+                    params: vec![serde_json::json!(deleted_at.to_rfc3339()), serde_json::json!(parent_id)],
+                });
+                total_stats.conflicts += 1;
+            }
+        }
     }
 
     Ok(MergeOutput {
@@ -149,26 +936,98 @@ pub fn merge_vaults(input: MergeInput) -> VaultResult<MergeOutput> {
     })
 }
 
-/// Merge a JSON string input and return JSON string output.
-/// Convenience function for FFI.
-pub fn merge_vaults_json(input_json: &str) -> VaultResult<String> {
+/// Merge a JSON string input with tombstone-aware conflict resolution and
+/// return JSON string output. Convenience function for FFI.
+pub fn merge_vaults_tombstone_aware_json(input_json: &str) -> VaultResult<String> {
     let input: MergeInput = serde_json::from_str(input_json)?;
-    let output = merge_vaults(input)?;
+    let output = merge_vaults_tombstone_aware(input)?;
     let output_json = serde_json::to_string(&output)?;
     Ok(output_json)
 }
 
-/// Merge table records by Id (standard merge).
-/// Returns SQL statements to apply to local database.
-fn merge_table_by_id(
+/// Returns true if a record's `IsDeleted` column is truthy.
+fn is_deleted(record: &Record) -> bool {
+    record
+        .get("IsDeleted")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// Get the `DeletedAt` timestamp from a record, using the same format
+/// handling as [`get_updated_at`].
+fn get_deleted_at(record: &Record) -> Option<DateTime<Utc>> {
+    record
+        .get("DeletedAt")
+        .and_then(|v| v.as_str())
+        .and_then(|s| {
+            DateTime::parse_from_rfc3339(s)
+                .map(|dt| dt.with_timezone(&Utc))
+                .ok()
+                .or_else(|| {
+                    chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f")
+                        .ok()
+                        .map(|naive| naive.and_utc())
+                })
+        })
+}
+
+/// Returns the record's own `DeletedAt` if `IsDeleted` is set.
+fn own_tombstone(record: &Record) -> Option<DateTime<Utc>> {
+    is_deleted(record).then(|| get_deleted_at(record)).flatten()
+}
+
+/// Maps every already-deleted record's Id to its `DeletedAt`, for tables
+/// that only exist on one side of the merge (nothing to resolve, but a
+/// cascade to child tables may still be needed).
+fn tombstones_from_records(records: &[Record]) -> HashMap<String, DateTime<Utc>> {
+    records
+        .iter()
+        .filter_map(|r| Some((get_record_id(r)?, own_tombstone(r)?)))
+        .collect()
+}
+
+/// Decide which side wins a tombstone-aware conflict.
+///
+/// Returns `(attributed_to_server, final_record, deleted_at)`: `final_record`
+/// is the record whose columns should end up in the database, and
+/// `deleted_at` is `Some` when the merged record is deleted, for cascading
+/// to child tables.
+///
+/// If either side carries a deletion at or after the other side's newest
+/// content timestamp, the deletion wins and the merged record stays deleted;
+/// otherwise the newer `UpdatedAt` wins as plain content, so only a content
+/// update strictly newer than a deletion can resurrect the record. A missing
+/// or unparseable `UpdatedAt` sorts as older than any parsed timestamp.
+fn resolve_tombstone_winner<'a>(local: &'a Record, server: &'a Record) -> (bool, &'a Record, Option<DateTime<Utc>>) {
+    let l_updated = get_updated_at(local);
+    let s_updated = get_updated_at(server);
+    let l_deleted_at = own_tombstone(local);
+    let s_deleted_at = own_tombstone(server);
+
+    if l_deleted_at.is_some() && l_deleted_at >= s_updated {
+        (false, local, l_deleted_at)
+    } else if s_deleted_at.is_some() && s_deleted_at >= l_updated {
+        (true, server, s_deleted_at)
+    } else if s_updated > l_updated {
+        (true, server, None)
+    } else {
+        (false, local, None)
+    }
+}
+
+/// Merge table records by Id with tombstone-aware conflict resolution.
+/// Returns the SQL statements to apply and the Ids of records whose merged
+/// state is deleted (for cascading to child tables).
+fn merge_table_by_id_tombstone_aware(
     table_name: &str,
     local_records: &[Record],
     server_records: &[Record],
+    allowed_columns: &[&str],
     stats: &mut MergeStats,
-) -> Vec<SqlStatement> {
+) -> (Vec<SqlStatement>, HashMap<String, DateTime<Utc>>) {
     let mut statements: Vec<SqlStatement> = Vec::new();
+    let mut deleted_ids: HashMap<String, DateTime<Utc>> = HashMap::new();
 
-    // Create map of server records by Id
     let mut server_map: HashMap<String, &Record> = HashMap::new();
     for record in server_records {
         if let Some(id) = get_record_id(record) {
@@ -176,7 +1035,6 @@ fn merge_table_by_id(
         }
     }
 
-    // Process local records
     for local_record in local_records {
         let local_id = match get_record_id(local_record) {
             Some(id) => id,
@@ -184,58 +1042,60 @@ fn merge_table_by_id(
         };
 
         if let Some(server_record) = server_map.get(&local_id) {
-            // Record exists in both - compare UpdatedAt for LWW
-            let local_ts = get_updated_at(local_record);
-            let server_ts = get_updated_at(server_record);
-
-            match (server_ts, local_ts) {
-                (Some(s_ts), Some(l_ts)) if s_ts > l_ts => {
-                    // Server wins - generate UPDATE
-                    stats.conflicts += 1;
-                    stats.records_from_server += 1;
-                    if let Some(stmt) = generate_update_sql(table_name, server_record, &local_id) {
-                        statements.push(stmt);
-                    }
-                }
-                _ => {
-                    // Local wins - no action needed
-                    stats.records_from_local += 1;
+            let (attributed_to_server, winner, deleted_at) =
+                resolve_tombstone_winner(local_record, server_record);
+
+            if attributed_to_server {
+                stats.conflicts += 1;
+                stats.records_from_server += 1;
+                if let Some(stmt) = generate_update_sql(table_name, winner, &local_id, allowed_columns, stats) {
+                    statements.push(stmt);
                 }
+            } else {
+                stats.records_from_local += 1;
+            }
+            if let Some(ts) = deleted_at {
+                deleted_ids.insert(local_id.clone(), ts);
             }
             server_map.remove(&local_id);
         } else {
-            // Only in local (created offline) - no action needed
             stats.records_created_locally += 1;
+            if let Some(ts) = own_tombstone(local_record) {
+                deleted_ids.insert(local_id.clone(), ts);
+            }
         }
     }
 
-    // Server-only records - generate INSERTs
-    for server_record in server_map.values() {
+    for (server_id, server_record) in server_map {
         stats.records_inserted += 1;
-        if let Some(stmt) = generate_insert_sql(table_name, server_record) {
+        if let Some(stmt) = generate_insert_sql(table_name, server_record, allowed_columns, stats) {
             statements.push(stmt);
         }
+        if let Some(ts) = own_tombstone(server_record) {
+            deleted_ids.insert(server_id, ts);
+        }
     }
 
-    statements
+    (statements, deleted_ids)
 }
 
-/// Merge table by composite key.
-/// Returns SQL statements to apply to local database.
-fn merge_table_by_composite_key(
+/// Merge table by composite key with tombstone-aware conflict resolution.
+/// Returns the SQL statements to apply and the Ids of records whose merged
+/// state is deleted (for cascading to child tables).
+fn merge_table_by_composite_key_tombstone_aware(
     table_name: &str,
     local_records: &[Record],
     server_records: &[Record],
     key_columns: &[&str],
+    allowed_columns: &[&str],
     stats: &mut MergeStats,
-) -> Vec<SqlStatement> {
+) -> (Vec<SqlStatement>, HashMap<String, DateTime<Utc>>) {
     let mut statements: Vec<SqlStatement> = Vec::new();
+    let mut deleted_ids: HashMap<String, DateTime<Utc>> = HashMap::new();
 
-    // Create map of server records by composite key
     let mut server_map: HashMap<String, &Record> = HashMap::new();
     for record in server_records {
         let key = get_composite_key(record, key_columns);
-        // Keep the one with latest UpdatedAt if duplicate keys
         if let Some(existing) = server_map.get(&key) {
             if get_updated_at(record) > get_updated_at(existing) {
                 server_map.insert(key, record);
@@ -245,50 +1105,49 @@ fn merge_table_by_composite_key(
         }
     }
 
-    // Process local records
     for local_record in local_records {
         let composite_key = get_composite_key(local_record, key_columns);
-
         let local_id = match get_record_id(local_record) {
             Some(id) => id,
             None => continue,
         };
 
         if let Some(server_record) = server_map.get(&composite_key) {
-            // Record exists in both - compare UpdatedAt
-            let local_ts = get_updated_at(local_record);
-            let server_ts = get_updated_at(server_record);
-
-            match (server_ts, local_ts) {
-                (Some(s_ts), Some(l_ts)) if s_ts > l_ts => {
-                    // Server wins - update with server data but keep local Id
-                    stats.conflicts += 1;
-                    stats.records_from_server += 1;
-                    if let Some(stmt) = generate_update_sql(table_name, server_record, &local_id) {
-                        statements.push(stmt);
-                    }
-                }
-                _ => {
-                    // Local wins - no action needed
-                    stats.records_from_local += 1;
+            let (attributed_to_server, winner, deleted_at) =
+                resolve_tombstone_winner(local_record, server_record);
+
+            if attributed_to_server {
+                stats.conflicts += 1;
+                stats.records_from_server += 1;
+                if let Some(stmt) = generate_update_sql(table_name, winner, &local_id, allowed_columns, stats) {
+                    statements.push(stmt);
                 }
+            } else {
+                stats.records_from_local += 1;
+            }
+            if let Some(ts) = deleted_at {
+                deleted_ids.insert(local_id.clone(), ts);
             }
             server_map.remove(&composite_key);
         } else {
-            // Only in local - no action needed
             stats.records_created_locally += 1;
+            if let Some(ts) = own_tombstone(local_record) {
+                deleted_ids.insert(local_id.clone(), ts);
+            }
         }
     }
 
-    // Server-only records (by composite key) - generate INSERTs
-    for (_key, server_record) in &server_map {
+    for (_key, server_record) in server_map {
         stats.records_inserted += 1;
-        if let Some(stmt) = generate_insert_sql(table_name, server_record) {
+        if let Some(stmt) = generate_insert_sql(table_name, server_record, allowed_columns, stats) {
             statements.push(stmt);
         }
+        if let (Some(server_id), Some(ts)) = (get_record_id(server_record), own_tombstone(server_record)) {
+            deleted_ids.insert(server_id, ts);
+        }
     }
 
-    statements
+    (statements, deleted_ids)
 }
 
 /// Get the Id field from a record.
@@ -317,6 +1176,25 @@ fn get_updated_at(record: &Record) -> Option<DateTime<Utc>> {
         })
 }
 
+/// Get an arbitrary companion timestamp column from a record (e.g.
+/// `Name_UpdatedAt` for [`types::TableConfig::lww_columns`]), using the same
+/// format handling as [`get_updated_at`].
+fn get_column_timestamp(record: &Record, column: &str) -> Option<DateTime<Utc>> {
+    record
+        .get(column)
+        .and_then(|v| v.as_str())
+        .and_then(|s| {
+            DateTime::parse_from_rfc3339(s)
+                .map(|dt| dt.with_timezone(&Utc))
+                .ok()
+                .or_else(|| {
+                    chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f")
+                        .ok()
+                        .map(|naive| naive.and_utc())
+                })
+        })
+}
+
 /// Generate composite key from specified columns.
 /// Concatenates column values with ":" separator.
 fn get_composite_key(record: &Record, key_columns: &[&str]) -> String {
@@ -332,9 +1210,29 @@ fn get_composite_key(record: &Record, key_columns: &[&str]) -> String {
         .join(":")
 }
 
+/// Returns whether `column` is in `allowed_columns`; if not, records it as a
+/// [`RejectedIdentifier`] so the caller can drop it from the generated SQL
+/// instead of interpolating an identifier this table's schema doesn't have.
+fn filter_column(table_name: &str, column: &str, allowed_columns: &[&str], stats: &mut MergeStats) -> bool {
+    if allowed_columns.contains(&column) {
+        true
+    } else {
+        stats.rejected_identifiers.push(RejectedIdentifier {
+            table: table_name.to_string(),
+            column: column.to_string(),
+        });
+        false
+    }
+}
+
 /// Generate an INSERT SQL statement for a record.
 /// Uses INSERT OR REPLACE to handle potential conflicts.
-fn generate_insert_sql(table_name: &str, record: &Record) -> Option<SqlStatement> {
+fn generate_insert_sql(
+    table_name: &str,
+    record: &Record,
+    allowed_columns: &[&str],
+    stats: &mut MergeStats,
+) -> Option<SqlStatement> {
     if record.is_empty() {
         return None;
     }
@@ -342,6 +1240,11 @@ fn generate_insert_sql(table_name: &str, record: &Record) -> Option<SqlStatement
     // Sort column names for consistent ordering
     let mut columns: Vec<&String> = record.keys().collect();
     columns.sort();
+    columns.retain(|c| filter_column(table_name, c, allowed_columns, stats));
+
+    if columns.is_empty() {
+        return None;
+    }
 
     let column_list = columns.iter().map(|c| c.as_str()).collect::<Vec<_>>().join(", ");
     let placeholders = columns.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
@@ -357,7 +1260,13 @@ fn generate_insert_sql(table_name: &str, record: &Record) -> Option<SqlStatement
 
 /// Generate an UPDATE SQL statement for a record.
 /// Updates all columns except Id, which is used in the WHERE clause.
-fn generate_update_sql(table_name: &str, record: &Record, id: &str) -> Option<SqlStatement> {
+fn generate_update_sql(
+    table_name: &str,
+    record: &Record,
+    id: &str,
+    allowed_columns: &[&str],
+    stats: &mut MergeStats,
+) -> Option<SqlStatement> {
     if record.is_empty() {
         return None;
     }
@@ -365,6 +1274,7 @@ fn generate_update_sql(table_name: &str, record: &Record, id: &str) -> Option<Sq
     // Sort column names for consistent ordering, excluding Id
     let mut columns: Vec<&String> = record.keys().filter(|c| *c != "Id").collect();
     columns.sort();
+    columns.retain(|c| filter_column(table_name, c, allowed_columns, stats));
 
     if columns.is_empty() {
         return None;
@@ -384,10 +1294,47 @@ fn generate_update_sql(table_name: &str, record: &Record, id: &str) -> Option<Sq
     Some(SqlStatement { sql, params })
 }
 
+/// Generate a DELETE SQL statement for a record identified by Id.
+fn generate_delete_sql(table_name: &str, id: &str) -> SqlStatement {
+    SqlStatement {
+        sql: format!("DELETE FROM {} WHERE Id = ?", table_name),
+        params: vec![serde_json::json!(id)],
+    }
+}
+
+/// Generate a DELETE SQL statement for a record identified by composite key columns.
+fn generate_delete_sql_composite(table_name: &str, record: &Record, key_columns: &[&str]) -> SqlStatement {
+    let where_clause = key_columns
+        .iter()
+        .map(|c| format!("{} = ?", c))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    let params: Vec<serde_json::Value> = key_columns
+        .iter()
+        .map(|c| record.get(*c).cloned().unwrap_or(serde_json::Value::Null))
+        .collect();
+
+    SqlStatement {
+        sql: format!("DELETE FROM {} WHERE {}", table_name, where_clause),
+        params,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Columns exercised by the fixtures below for the fictional "Test"
+    /// table used in merge-logic tests that aren't about schema validation.
+    const TEST_ALLOWED_COLUMNS: &[&str] =
+        &["Id", "UpdatedAt", "IsDeleted", "DeletedAt", "Name", "Name_UpdatedAt", "Username", "Password", "Notes"];
+
+    const TEST_TABLE_CONFIG: TableConfig = TableConfig::new("Test", TEST_ALLOWED_COLUMNS);
+
+    fn items_allowed_columns() -> &'static [&'static str] {
+        SYNCABLE_TABLES.iter().find(|t| t.name == "Items").unwrap().allowed_columns
+    }
+
     fn make_record(id: &str, updated_at: &str) -> Record {
         let mut record = HashMap::new();
         record.insert("Id".to_string(), serde_json::json!(id));
@@ -402,7 +1349,7 @@ mod tests {
         let server = vec![make_record("1", "2024-01-01T00:00:00Z")];
         let mut stats = MergeStats::default();
 
-        let statements = merge_table_by_id("Test", &local, &server, &mut stats);
+        let statements = merge_table_by_id(&local, &server, None, &TEST_TABLE_CONFIG, &mut stats);
 
         assert_eq!(stats.records_from_local, 1);
         assert_eq!(stats.records_from_server, 0);
@@ -415,7 +1362,7 @@ mod tests {
         let server = vec![make_record("1", "2024-01-02T00:00:00Z")];
         let mut stats = MergeStats::default();
 
-        let statements = merge_table_by_id("Test", &local, &server, &mut stats);
+        let statements = merge_table_by_id(&local, &server, None, &TEST_TABLE_CONFIG, &mut stats);
 
         assert_eq!(stats.records_from_server, 1);
         assert_eq!(stats.conflicts, 1);
@@ -429,7 +1376,7 @@ mod tests {
         let server = vec![make_record("1", "2024-01-01T00:00:00Z")];
         let mut stats = MergeStats::default();
 
-        let statements = merge_table_by_id("Test", &local, &server, &mut stats);
+        let statements = merge_table_by_id(&local, &server, None, &TEST_TABLE_CONFIG, &mut stats);
 
         assert_eq!(stats.records_inserted, 1);
         assert_eq!(statements.len(), 1);
@@ -442,12 +1389,173 @@ mod tests {
         let server: Vec<Record> = vec![];
         let mut stats = MergeStats::default();
 
-        let statements = merge_table_by_id("Test", &local, &server, &mut stats);
+        let statements = merge_table_by_id(&local, &server, None, &TEST_TABLE_CONFIG, &mut stats);
 
         assert_eq!(stats.records_created_locally, 1);
         assert!(statements.is_empty()); // No SQL needed
     }
 
+    #[test]
+    fn test_plain_lww_deletes_when_server_tombstone_is_newer() {
+        let local = vec![make_record("1", "2024-01-01T00:00:00Z")];
+        let server = vec![make_deleted_record("1", "2024-01-02T00:00:00Z", "2024-01-02T00:00:00Z")];
+        let mut stats = MergeStats::default();
+
+        let statements = merge_table_by_id(&local, &server, None, &TEST_TABLE_CONFIG, &mut stats);
+
+        assert_eq!(stats.records_deleted, 1);
+        assert_eq!(statements.len(), 1);
+        assert_eq!(statements[0].sql, "DELETE FROM Test WHERE Id = ?");
+        assert_eq!(statements[0].params, vec![serde_json::json!("1")]);
+    }
+
+    #[test]
+    fn test_plain_lww_server_only_tombstone_not_inserted() {
+        let local: Vec<Record> = vec![];
+        let server = vec![make_deleted_record("1", "2024-01-01T00:00:00Z", "2024-01-01T00:00:00Z")];
+        let mut stats = MergeStats::default();
+
+        let statements = merge_table_by_id(&local, &server, None, &TEST_TABLE_CONFIG, &mut stats);
+
+        assert_eq!(stats.records_inserted, 0);
+        assert!(statements.is_empty());
+    }
+
+    fn make_full_record(id: &str, updated_at: &str, username: &str, password: &str) -> Record {
+        let mut record = HashMap::new();
+        record.insert("Id".to_string(), serde_json::json!(id));
+        record.insert("UpdatedAt".to_string(), serde_json::json!(updated_at));
+        record.insert("Username".to_string(), serde_json::json!(username));
+        record.insert("Password".to_string(), serde_json::json!(password));
+        record
+    }
+
+    #[test]
+    fn test_three_way_merge_non_overlapping_fields_both_applied() {
+        let base = vec![make_full_record("1", "2024-01-01T00:00:00Z", "alice", "old-pw")];
+        // Local only changed Username; server only changed Password.
+        let local = vec![make_full_record("1", "2024-01-02T00:00:00Z", "alice2", "old-pw")];
+        let server = vec![make_full_record("1", "2024-01-02T00:00:00Z", "alice", "new-pw")];
+        let mut stats = MergeStats::default();
+
+        let statements = merge_table_by_id(&local, &server, Some(&base), &TEST_TABLE_CONFIG, &mut stats);
+
+        assert_eq!(statements.len(), 1);
+        let stmt = &statements[0];
+        // Password should be pulled from server; Username untouched since it
+        // already matches local's own (unchanged-on-server) value.
+        assert!(stmt.sql.contains("Password = ?"));
+        assert!(!stmt.sql.contains("Username = ?"));
+        assert_eq!(stats.field_conflicts, 0);
+        assert_eq!(stats.conflicts, 1);
+    }
+
+    #[test]
+    fn test_three_way_merge_same_field_conflict_falls_back_to_lww() {
+        let base = vec![make_full_record("1", "2024-01-01T00:00:00Z", "alice", "old-pw")];
+        // Both sides changed Password to different values; server is newer.
+        let local = vec![make_full_record("1", "2024-01-02T00:00:00Z", "alice", "local-pw")];
+        let server = vec![make_full_record("1", "2024-01-03T00:00:00Z", "alice", "server-pw")];
+        let mut stats = MergeStats::default();
+
+        let statements = merge_table_by_id(&local, &server, Some(&base), &TEST_TABLE_CONFIG, &mut stats);
+
+        assert_eq!(statements.len(), 1);
+        assert_eq!(stats.field_conflicts, 1);
+        assert!(statements[0].params.iter().any(|p| p == &serde_json::json!("server-pw")));
+    }
+
+    #[test]
+    fn test_three_way_merge_winning_deletion_emits_delete_not_update() {
+        let mut base = make_full_record("1", "2024-01-01T00:00:00Z", "alice", "old-pw");
+        base.insert("IsDeleted".to_string(), serde_json::json!(false));
+        // Local made an unrelated edit; server independently deleted the record.
+        let mut local = make_full_record("1", "2024-01-02T00:00:00Z", "alice2", "old-pw");
+        local.insert("IsDeleted".to_string(), serde_json::json!(false));
+        let mut server = make_full_record("1", "2024-01-03T00:00:00Z", "alice", "old-pw");
+        server.insert("IsDeleted".to_string(), serde_json::json!(true));
+        server.insert("DeletedAt".to_string(), serde_json::json!("2024-01-03T00:00:00Z"));
+        let mut stats = MergeStats::default();
+
+        let statements = merge_table_by_id(&[local], &[server], Some(&[base]), &TEST_TABLE_CONFIG, &mut stats);
+
+        assert_eq!(statements.len(), 1);
+        assert_eq!(statements[0].sql, "DELETE FROM Test WHERE Id = ?");
+        assert_eq!(statements[0].params, vec![serde_json::json!("1")]);
+        assert_eq!(stats.records_deleted, 1);
+    }
+
+    #[test]
+    fn test_three_way_merge_no_base_match_falls_back_to_two_way() {
+        // No base record for Id "1" - falls back to plain two-way LWW.
+        let base = vec![make_full_record("other-id", "2024-01-01T00:00:00Z", "alice", "old-pw")];
+        let local = vec![make_full_record("1", "2024-01-01T00:00:00Z", "alice", "local-pw")];
+        let server = vec![make_full_record("1", "2024-01-02T00:00:00Z", "alice", "server-pw")];
+        let mut stats = MergeStats::default();
+
+        let statements = merge_table_by_id(&local, &server, Some(&base), &TEST_TABLE_CONFIG, &mut stats);
+
+        assert_eq!(statements.len(), 1);
+        assert_eq!(stats.field_conflicts, 0);
+        assert_eq!(stats.conflicts, 1);
+    }
+
+    fn make_record_with_name(id: &str, updated_at: &str, name: &str, name_updated_at: &str) -> Record {
+        let mut record = make_record(id, updated_at);
+        record.insert("Name".to_string(), serde_json::json!(name));
+        record.insert("Name_UpdatedAt".to_string(), serde_json::json!(name_updated_at));
+        record
+    }
+
+    #[test]
+    fn test_field_lww_keeps_both_sides_concurrent_edits_to_different_columns() {
+        let lww_columns: &[(&str, &str)] = &[("Name", "Name_UpdatedAt")];
+        // Local's overall row is newer, but the server changed Name more
+        // recently - whole-row LWW would drop the server's rename.
+        let local = make_record_with_name("1", "2024-01-05T00:00:00Z", "Local Edit", "2024-01-01T00:00:00Z");
+        let server = make_record_with_name("1", "2024-01-02T00:00:00Z", "Server Rename", "2024-01-04T00:00:00Z");
+        let mut stats = MergeStats::default();
+
+        let stmt = merge_record_field_lww("Test", &local, &server, lww_columns, "1", TEST_ALLOWED_COLUMNS, &mut stats).unwrap();
+
+        assert!(stmt.sql.contains("Name = ?"));
+        assert!(stmt.sql.contains("Name_UpdatedAt = ?"));
+        assert!(stmt.params.contains(&serde_json::json!("Server Rename")));
+    }
+
+    #[test]
+    fn test_field_lww_keeps_local_on_companion_timestamp_tie() {
+        let lww_columns: &[(&str, &str)] = &[("Name", "Name_UpdatedAt")];
+        let local = make_record_with_name("1", "2024-01-01T00:00:00Z", "Local Name", "2024-01-03T00:00:00Z");
+        let server = make_record_with_name("1", "2024-01-02T00:00:00Z", "Server Name", "2024-01-03T00:00:00Z");
+        let mut stats = MergeStats::default();
+
+        let stmt = merge_record_field_lww("Test", &local, &server, lww_columns, "1", TEST_ALLOWED_COLUMNS, &mut stats);
+
+        // Name is tied, so local wins and is left untouched; only the
+        // record-level UpdatedAt stamp (server is newer there) changes.
+        let stmt = stmt.unwrap();
+        assert!(!stmt.sql.contains("Name = ?"));
+        assert!(stmt.sql.contains("UpdatedAt = ?"));
+    }
+
+    #[test]
+    fn test_field_lww_column_without_companion_falls_back_to_record_level() {
+        let lww_columns: &[(&str, &str)] = &[("Name", "Name_UpdatedAt")];
+        let mut local = make_record_with_name("1", "2024-01-01T00:00:00Z", "Same Name", "2024-01-01T00:00:00Z");
+        local.insert("Notes".to_string(), serde_json::json!("local notes"));
+        let mut server = make_record_with_name("1", "2024-01-02T00:00:00Z", "Same Name", "2024-01-01T00:00:00Z");
+        server.insert("Notes".to_string(), serde_json::json!("server notes"));
+        let mut stats = MergeStats::default();
+
+        let stmt = merge_record_field_lww("Test", &local, &server, lww_columns, "1", TEST_ALLOWED_COLUMNS, &mut stats).unwrap();
+
+        // Notes has no companion timestamp, so it follows the server-is-newer
+        // record-level comparison.
+        assert!(stmt.sql.contains("Notes = ?"));
+        assert!(stmt.params.contains(&serde_json::json!("server notes")));
+    }
+
     #[test]
     fn test_merge_vaults_json() {
         let input = MergeInput {
@@ -459,6 +1567,7 @@ mod tests {
                 name: "Items".to_string(),
                 records: vec![make_record("1", "2024-01-02T00:00:00Z")],
             }],
+            base_tables: vec![],
         };
 
         let input_json = serde_json::to_string(&input).unwrap();
@@ -475,19 +1584,24 @@ mod tests {
     #[test]
     fn test_generate_insert_sql() {
         let record = make_record("test-id", "2024-01-01T00:00:00Z");
-        let stmt = generate_insert_sql("Items", &record).unwrap();
+        let mut stats = MergeStats::default();
+        let allowed = SYNCABLE_TABLES.iter().find(|t| t.name == "Items").unwrap().allowed_columns;
+        let stmt = generate_insert_sql("Items", &record, allowed, &mut stats).unwrap();
 
         assert!(stmt.sql.contains("INSERT OR REPLACE INTO Items"));
         assert!(stmt.sql.contains("Id"));
         assert!(stmt.sql.contains("Name"));
         assert!(stmt.sql.contains("UpdatedAt"));
         assert_eq!(stmt.params.len(), 3);
+        assert!(stats.rejected_identifiers.is_empty());
     }
 
     #[test]
     fn test_generate_update_sql() {
         let record = make_record("test-id", "2024-01-01T00:00:00Z");
-        let stmt = generate_update_sql("Items", &record, "test-id").unwrap();
+        let mut stats = MergeStats::default();
+        let allowed = SYNCABLE_TABLES.iter().find(|t| t.name == "Items").unwrap().allowed_columns;
+        let stmt = generate_update_sql("Items", &record, "test-id", allowed, &mut stats).unwrap();
 
         assert!(stmt.sql.starts_with("UPDATE Items SET"));
         assert!(stmt.sql.contains("WHERE Id = ?"));
@@ -498,4 +1612,186 @@ mod tests {
         // Last param should be the Id
         assert_eq!(stmt.params[2], serde_json::json!("test-id"));
     }
+
+    #[test]
+    fn test_generate_insert_sql_drops_unknown_column() {
+        let mut record = make_record("test-id", "2024-01-01T00:00:00Z");
+        record.insert("Evil; DROP TABLE Items; --".to_string(), serde_json::json!("x"));
+        let mut stats = MergeStats::default();
+        let allowed = SYNCABLE_TABLES.iter().find(|t| t.name == "Items").unwrap().allowed_columns;
+        let stmt = generate_insert_sql("Items", &record, allowed, &mut stats).unwrap();
+
+        assert!(!stmt.sql.contains("DROP TABLE"));
+        assert_eq!(stats.rejected_identifiers.len(), 1);
+        assert_eq!(stats.rejected_identifiers[0].table, "Items");
+        assert_eq!(stats.rejected_identifiers[0].column, "Evil; DROP TABLE Items; --");
+    }
+
+    fn make_deleted_record(id: &str, updated_at: &str, deleted_at: &str) -> Record {
+        let mut record = make_record(id, updated_at);
+        record.insert("IsDeleted".to_string(), serde_json::json!(true));
+        record.insert("DeletedAt".to_string(), serde_json::json!(deleted_at));
+        record
+    }
+
+    #[test]
+    fn test_tombstone_aware_stale_edit_does_not_resurrect() {
+        // Server deleted the item at 01-03; local still has a stale edit from
+        // 01-02 that never saw the delete. Plain LWW would resurrect it.
+        let local = vec![make_record("1", "2024-01-02T00:00:00Z")];
+        let server = vec![make_deleted_record("1", "2024-01-01T00:00:00Z", "2024-01-03T00:00:00Z")];
+        let mut stats = MergeStats::default();
+
+        let (statements, deleted) = merge_table_by_id_tombstone_aware("Items", &local, &server, items_allowed_columns(), &mut stats);
+
+        assert_eq!(stats.records_from_server, 1);
+        assert_eq!(statements.len(), 1);
+        assert_eq!(statements[0].params[statements[0].params.len() - 1], serde_json::json!("1"));
+        assert!(deleted.contains_key("1"));
+    }
+
+    #[test]
+    fn test_tombstone_aware_resurrection_allowed_when_edit_is_newer() {
+        // Item was deleted at 01-01, but a genuinely newer edit at 01-05
+        // should resurrect it rather than staying deleted.
+        let local = vec![make_deleted_record("1", "2023-12-31T00:00:00Z", "2024-01-01T00:00:00Z")];
+        let server = vec![make_record("1", "2024-01-05T00:00:00Z")];
+        let mut stats = MergeStats::default();
+
+        let (statements, deleted) = merge_table_by_id_tombstone_aware("Items", &local, &server, items_allowed_columns(), &mut stats);
+
+        assert_eq!(stats.records_from_server, 1);
+        assert_eq!(statements.len(), 1);
+        assert!(!deleted.contains_key("1"));
+    }
+
+    #[test]
+    fn test_tombstone_aware_both_undeleted_behaves_like_plain_lww() {
+        let local = vec![make_record("1", "2024-01-01T00:00:00Z")];
+        let server = vec![make_record("1", "2024-01-02T00:00:00Z")];
+        let mut stats = MergeStats::default();
+
+        let (statements, deleted) = merge_table_by_id_tombstone_aware("Items", &local, &server, items_allowed_columns(), &mut stats);
+
+        assert_eq!(stats.records_from_server, 1);
+        assert_eq!(statements.len(), 1);
+        assert!(!deleted.contains_key("1"));
+    }
+
+    #[test]
+    fn test_tombstone_aware_cascades_deletion_to_child_table() {
+        let field_value = {
+            let mut record = HashMap::new();
+            record.insert("Id".to_string(), serde_json::json!("fv-1"));
+            record.insert("ItemId".to_string(), serde_json::json!("1"));
+            record.insert("FieldKey".to_string(), serde_json::json!("password"));
+            record.insert("UpdatedAt".to_string(), serde_json::json!("2024-01-01T00:00:00Z"));
+            record
+        };
+
+        let input = MergeInput {
+            local_tables: vec![
+                TableData { name: "Items".to_string(), records: vec![make_record("1", "2024-01-01T00:00:00Z")] },
+                TableData { name: "FieldValues".to_string(), records: vec![field_value.clone()] },
+            ],
+            server_tables: vec![
+                TableData {
+                    name: "Items".to_string(),
+                    records: vec![make_deleted_record("1", "2024-01-01T00:00:00Z", "2024-01-02T00:00:00Z")],
+                },
+                TableData { name: "FieldValues".to_string(), records: vec![field_value] },
+            ],
+            base_tables: vec![],
+        };
+
+        let output = merge_vaults_tombstone_aware(input).unwrap();
+
+        assert!(output.success);
+        let field_value_update = output
+            .statements
+            .iter()
+            .find(|s| s.sql.starts_with("UPDATE FieldValues"))
+            .expect("deleting the parent Item should cascade an UPDATE to FieldValues");
+        assert!(field_value_update.sql.contains("WHERE ItemId = ? AND IsDeleted = 0"));
+        assert_eq!(field_value_update.params[1], serde_json::json!("1"));
+    }
+
+    #[test]
+    fn test_tombstone_aware_does_not_cascade_when_parent_not_deleted() {
+        let field_value = {
+            let mut record = HashMap::new();
+            record.insert("Id".to_string(), serde_json::json!("fv-1"));
+            record.insert("ItemId".to_string(), serde_json::json!("1"));
+            record.insert("FieldKey".to_string(), serde_json::json!("password"));
+            record.insert("UpdatedAt".to_string(), serde_json::json!("2024-01-01T00:00:00Z"));
+            record
+        };
+
+        let input = MergeInput {
+            local_tables: vec![
+                TableData { name: "Items".to_string(), records: vec![make_record("1", "2024-01-01T00:00:00Z")] },
+                TableData { name: "FieldValues".to_string(), records: vec![field_value.clone()] },
+            ],
+            server_tables: vec![
+                TableData { name: "Items".to_string(), records: vec![make_record("1", "2024-01-02T00:00:00Z")] },
+                TableData { name: "FieldValues".to_string(), records: vec![field_value] },
+            ],
+            base_tables: vec![],
+        };
+
+        let output = merge_vaults_tombstone_aware(input).unwrap();
+
+        assert!(!output.statements.iter().any(|s| s.sql.starts_with("UPDATE FieldValues")));
+    }
+
+    #[test]
+    fn test_tombstone_aware_rebalances_colliding_positions() {
+        let make_folder = |id: &str, rank: &str| {
+            let mut record = make_record(id, "2024-01-01T00:00:00Z");
+            record.insert("Rank".to_string(), serde_json::json!(rank));
+            record
+        };
+        // Both sides agree on the same (colliding) ranks - nothing for the
+        // per-table merge itself to resolve, so any emitted rank UPDATE must
+        // come from rebalance_positions.
+        let folders = vec![make_folder("f1", "m"), make_folder("f2", "m")];
+
+        let input = MergeInput {
+            local_tables: vec![TableData { name: "Folders".to_string(), records: folders.clone() }],
+            server_tables: vec![TableData { name: "Folders".to_string(), records: folders }],
+            base_tables: vec![],
+        };
+
+        let output = merge_vaults_tombstone_aware(input).unwrap();
+
+        assert_eq!(output.stats.positions_rebalanced, 1);
+        assert!(output.statements.iter().any(|s| s.sql == "UPDATE Folders SET Rank = ? WHERE Id = ?"));
+    }
+
+    #[test]
+    fn test_rebalance_positions_groups_by_rank_not_by_id() {
+        let make_folder = |id: &str, rank: &str| {
+            let mut record = make_record(id, "2024-01-01T00:00:00Z");
+            record.insert("Rank".to_string(), serde_json::json!(rank));
+            record
+        };
+        // Ids and ranks are deliberately interleaved: sorting by id ("1","2","3","4")
+        // puts the two "x" records and the two "y" records non-adjacent, so a sort
+        // that compares id before rank would miss both collisions entirely.
+        let folders = vec![
+            make_folder("1", "x"),
+            make_folder("2", "y"),
+            make_folder("3", "x"),
+            make_folder("4", "y"),
+        ];
+        let mut stats = MergeStats::default();
+
+        let statements = rebalance_positions("Folders", &folders, &[], "Rank", &mut stats);
+
+        assert_eq!(stats.positions_rebalanced, 2);
+        assert_eq!(statements.len(), 2);
+        let rebalanced_ids: Vec<&str> =
+            statements.iter().map(|s| s.params[1].as_str().unwrap()).collect();
+        assert_eq!(rebalanced_ids, vec!["3", "4"]);
+    }
 }
@@ -0,0 +1,136 @@
+//! Common types used across the vault merge module.
+
+/// Configuration for a syncable table.
+#[derive(Debug, Clone)]
+pub struct TableConfig {
+    /// Table name in the database
+    pub name: &'static str,
+    /// Columns to use for composite key matching (if any).
+    /// When empty, uses "Id" column for matching.
+    /// When set, these columns are concatenated to form the composite key.
+    pub composite_key_columns: &'static [&'static str],
+    /// Parent table whose tombstone cascades to this table (if any).
+    /// When set, a record whose `parent_id_column` value names a row that
+    /// ends up deleted in `parent_table` is forced deleted too, regardless
+    /// of this record's own timestamps.
+    pub parent_table: Option<&'static str>,
+    /// Column in this table's records holding the parent row's Id, used to
+    /// look it up in `parent_table`. Only meaningful when `parent_table` is set.
+    pub parent_id_column: Option<&'static str>,
+    /// Columns that carry their own companion timestamp column, e.g.
+    /// `("Name", "Name_UpdatedAt")`. When merging two non-deleted records
+    /// with no common `base_records` row, each of these columns is resolved
+    /// independently by comparing its companion timestamp rather than the
+    /// record's whole-row `UpdatedAt`, so concurrent edits to different
+    /// columns both survive. Columns not listed here still fall back to the
+    /// record-level `UpdatedAt` comparison.
+    pub lww_columns: &'static [(&'static str, &'static str)],
+    /// Column holding a fractional-index rank string for tables with
+    /// user-meaningful sibling order (e.g. a drag-and-drop folder/tag list).
+    /// When set, merge runs an extra rebalancing pass after the normal LWW
+    /// statements: any two records that end up sharing the same rank get one
+    /// of them moved to a freshly generated midpoint rank, so local and
+    /// server converge on the same order instead of depending on an integer
+    /// "reindex everything" pass. See `merge_vaults`'s position-rebalancing step.
+    pub position_column: Option<&'static str>,
+    /// Every column name this table's SQL schema actually has. A server
+    /// payload's JSON map keys end up interpolated directly into generated
+    /// SQL (`INSERT OR REPLACE INTO {table} ({column_list})` and friends), so
+    /// any record column not in this list is dropped rather than emitted, and
+    /// reported via `MergeStats::rejected_identifiers` - see `filter_column`.
+    pub allowed_columns: &'static [&'static str],
+}
+
+impl TableConfig {
+    pub const fn new(name: &'static str, allowed_columns: &'static [&'static str]) -> Self {
+        Self {
+            name,
+            composite_key_columns: &[],
+            parent_table: None,
+            parent_id_column: None,
+            lww_columns: &[],
+            position_column: None,
+            allowed_columns,
+        }
+    }
+
+    pub const fn with_composite_key(mut self, columns: &'static [&'static str]) -> Self {
+        self.composite_key_columns = columns;
+        self
+    }
+
+    /// Declares this table's records as children of `parent_table`, linked
+    /// via `parent_id_column`.
+    pub const fn with_parent(mut self, parent_table: &'static str, parent_id_column: &'static str) -> Self {
+        self.parent_table = Some(parent_table);
+        self.parent_id_column = Some(parent_id_column);
+        self
+    }
+
+    /// Declares columns that should be resolved as independent LWW
+    /// registers via their own companion timestamp column instead of the
+    /// record-level `UpdatedAt`.
+    pub const fn with_lww_columns(mut self, columns: &'static [(&'static str, &'static str)]) -> Self {
+        self.lww_columns = columns;
+        self
+    }
+
+    /// Declares the column holding this table's fractional-index rank.
+    pub const fn with_position_column(mut self, column: &'static str) -> Self {
+        self.position_column = Some(column);
+        self
+    }
+
+    /// Returns true if this table uses composite key matching.
+    pub const fn uses_composite_key(&self) -> bool {
+        !self.composite_key_columns.is_empty()
+    }
+}
+
+/// All tables that need LWW merge.
+/// FieldValues uses composite key (ItemId + FieldKey) for merging.
+/// FieldValues/Attachments/TotpCodes/Passkeys are children of Items, so a
+/// tombstoned Item cascades its deletion to them (see `merge_vaults_tombstone_aware`).
+pub static SYNCABLE_TABLES: &[TableConfig] = &[
+    // Name commonly gets renamed from two devices independently of other
+    // Item edits, so it carries its own companion timestamp rather than
+    // losing to whichever side happens to touch the row last.
+    TableConfig::new(
+        "Items",
+        &["Id", "UpdatedAt", "IsDeleted", "DeletedAt", "NeverExpire", "Name", "Name_UpdatedAt", "Type"],
+    )
+    .with_lww_columns(&[("Name", "Name_UpdatedAt")]),
+    TableConfig::new("FieldValues", &["Id", "ItemId", "FieldKey", "Value", "UpdatedAt", "IsDeleted", "DeletedAt"])
+        .with_composite_key(&["ItemId", "FieldKey"])
+        .with_parent("Items", "ItemId"),
+    TableConfig::new("Folders", &["Id", "UpdatedAt", "IsDeleted", "DeletedAt", "Name", "ParentFolderId", "Rank"])
+        .with_position_column("Rank"),
+    TableConfig::new("Tags", &["Id", "UpdatedAt", "IsDeleted", "DeletedAt", "Name", "Rank"])
+        .with_position_column("Rank"),
+    TableConfig::new("ItemTags", &["Id", "ItemId", "TagId", "UpdatedAt", "IsDeleted", "DeletedAt", "Rank"])
+        .with_position_column("Rank"),
+    TableConfig::new("Attachments", &["Id", "ItemId", "Filename", "Data", "UpdatedAt", "IsDeleted", "DeletedAt"])
+        .with_parent("Items", "ItemId"),
+    TableConfig::new("TotpCodes", &["Id", "ItemId", "Name", "SecretKey", "UpdatedAt", "IsDeleted", "DeletedAt"])
+        .with_parent("Items", "ItemId"),
+    TableConfig::new("Passkeys", &["Id", "ItemId", "CredentialId", "PublicKey", "UpdatedAt", "IsDeleted", "DeletedAt"])
+        .with_parent("Items", "ItemId"),
+    TableConfig::new("FieldDefinitions", &["Id", "FieldKey", "Label", "UpdatedAt", "IsDeleted", "DeletedAt"]),
+    TableConfig::new("FieldHistories", &["Id", "ItemId", "FieldKey", "Value", "UpdatedAt", "IsDeleted", "DeletedAt"]),
+    TableConfig::new("Logos", &["Id", "Domain", "Data", "UpdatedAt", "IsDeleted", "DeletedAt"]),
+];
+
+/// List of syncable table names (for clients to know which tables to read).
+pub const SYNCABLE_TABLE_NAMES: &[&str] = &[
+    "Items",
+    "FieldValues",
+    "Folders",
+    "Tags",
+    "ItemTags",
+    "Attachments",
+    "TotpCodes",
+    "Passkeys",
+    "FieldDefinitions",
+    "FieldHistories",
+    "Logos",
+];
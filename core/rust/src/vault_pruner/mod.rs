@@ -3,11 +3,22 @@
 //! This module handles the automatic cleanup of items that have been in the trash
 //! (DeletedAt set) for longer than the retention period (default 30 days).
 //! It generates SQL statements to permanently delete (IsDeleted = true) these items
-//! along with their related entities.
+//! along with their related entities, and appends a [`PruneJournalEntry`] per
+//! pruned item to a `PruneHistory` table so a user can audit what the
+//! automatic cleanup removed and when.
+//!
+//! The global `retention_days` can be overridden per item type or per item
+//! via [`RetentionOverrides`] (see [`effective_retention_days`]), and an item
+//! can be pinned to never expire with a `NeverExpire` flag on its `Items` row.
+//!
+//! By default everything above is a soft tombstone (`IsDeleted = 1`), kept
+//! around for sync. Setting `PruneInput::hard_delete` instead emits real
+//! `DELETE` statements and a trailing `VACUUM` to actually reclaim storage —
+//! only safe once the soft-delete tombstone has reached every device.
 
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::error::VaultResult;
 use crate::vault_merge::SqlStatement;
@@ -43,15 +54,99 @@ pub struct PruneInput {
     /// - Swift: `ISO8601DateFormatter().string(from: Date())`
     /// - Kotlin: `Instant.now().toString()` or `SimpleDateFormat("yyyy-MM-dd'T'HH:mm:ss.SSS'Z'", Locale.US).format(Date())`
     pub current_time: String,
-    /// Retention period in days (default: 30)
+    /// Retention period in days (default: 30), used when no more specific
+    /// override in `retention_overrides` applies to an item.
     #[serde(default = "default_retention_days")]
     pub retention_days: u32,
+    /// Per-type / per-item retention overrides layered over `retention_days`.
+    #[serde(default)]
+    pub retention_overrides: Option<RetentionOverrides>,
+    /// When true, emit real `DELETE` statements (children before parent,
+    /// followed by a trailing `VACUUM`) instead of soft `IsDeleted = 1`
+    /// tombstones, to actually reclaim storage and drop plaintext-adjacent
+    /// blobs from the SQLite file.
+    ///
+    /// Only run this once the soft-delete tombstone has propagated to every
+    /// device via sync: a hard-deleted row has no tombstone left to sync.
+    #[serde(default)]
+    pub hard_delete: bool,
 }
 
 fn default_retention_days() -> u32 {
     30
 }
 
+/// Retention overrides consulted before falling back to `retention_days`.
+/// For a given item, the most specific applicable policy wins: a per-item
+/// override beats a per-type override, which beats `retention_days`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetentionOverrides {
+    /// Retention period in days, keyed by the item's `Type` column
+    /// (e.g. `{"Attachment": 7}` to purge deleted attachments sooner than
+    /// the default).
+    #[serde(default)]
+    pub per_type_days: HashMap<String, u32>,
+    /// Retention period in days, keyed by item Id. Takes priority over
+    /// `per_type_days`.
+    #[serde(default)]
+    pub per_item_days: HashMap<String, u32>,
+}
+
+/// Builds the statement that removes the `Items` row for `item_id`: a soft
+/// tombstone by default, or a real `DELETE` when `hard_delete` is set.
+fn item_delete_statement(item_id: &str, hard_delete: bool, now_str: &str) -> SqlStatement {
+    if hard_delete {
+        SqlStatement {
+            sql: "DELETE FROM Items WHERE Id = ?".to_string(),
+            params: vec![serde_json::json!(item_id)],
+        }
+    } else {
+        SqlStatement {
+            sql: "UPDATE Items SET IsDeleted = 1, UpdatedAt = ? WHERE Id = ?".to_string(),
+            params: vec![
+                serde_json::json!(now_str),
+                serde_json::json!(item_id),
+            ],
+        }
+    }
+}
+
+/// Builds the statement that removes `table_name` rows belonging to
+/// `item_id`: a soft tombstone by default, or a real `DELETE` when
+/// `hard_delete` is set.
+fn child_delete_statement(table_name: &str, item_id: &str, hard_delete: bool, now_str: &str) -> SqlStatement {
+    if hard_delete {
+        SqlStatement {
+            sql: format!("DELETE FROM {} WHERE ItemId = ?", table_name),
+            params: vec![serde_json::json!(item_id)],
+        }
+    } else {
+        SqlStatement {
+            sql: format!("UPDATE {} SET IsDeleted = 1, UpdatedAt = ? WHERE ItemId = ? AND IsDeleted = 0", table_name),
+            params: vec![
+                serde_json::json!(now_str),
+                serde_json::json!(item_id),
+            ],
+        }
+    }
+}
+
+/// Resolves the retention period (in days) for a single item: a per-item
+/// override wins, then a per-type override, then `retention_days`.
+fn effective_retention_days(item: &Record, item_id: &str, input: &PruneInput) -> u32 {
+    if let Some(overrides) = &input.retention_overrides {
+        if let Some(&days) = overrides.per_item_days.get(item_id) {
+            return days;
+        }
+        if let Some(item_type) = item.get("Type").and_then(|v| v.as_str()) {
+            if let Some(&days) = overrides.per_type_days.get(item_type) {
+                return days;
+            }
+        }
+    }
+    input.retention_days
+}
+
 /// Statistics about what was pruned.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
@@ -66,6 +161,34 @@ pub struct PruneStats {
     pub totp_codes_pruned: u32,
     /// Number of passkeys permanently deleted
     pub passkeys_pruned: u32,
+    /// Number of child rows (FieldValues/Attachments/TotpCodes/Passkeys)
+    /// permanently deleted because their parent Item no longer exists or was
+    /// already hard-deleted, rather than because they were cascaded from an
+    /// item pruned this run
+    pub orphans_pruned: u32,
+}
+
+/// An audit record of a single permanently-deleted item, for reconciling
+/// "why did this credential disappear" across devices.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct PruneJournalEntry {
+    /// Id of the permanently deleted item
+    pub item_id: String,
+    /// The item's `Type` column, if the record carried one
+    pub item_type: Option<String>,
+    /// The item's original `DeletedAt` (when it was moved to trash)
+    pub deleted_at: String,
+    /// When this prune ran (same as `PruneInput::current_time`)
+    pub pruned_at: String,
+    /// Related FieldValues permanently deleted along with the item
+    pub field_values_removed: u32,
+    /// Related Attachments permanently deleted along with the item
+    pub attachments_removed: u32,
+    /// Related TotpCodes permanently deleted along with the item
+    pub totp_codes_removed: u32,
+    /// Related Passkeys permanently deleted along with the item
+    pub passkeys_removed: u32,
 }
 
 /// Output of the prune operation.
@@ -77,6 +200,9 @@ pub struct PruneOutput {
     pub statements: Vec<SqlStatement>,
     /// Statistics about what was pruned
     pub stats: PruneStats,
+    /// One entry per permanently deleted item, mirroring the `PruneHistory`
+    /// rows inserted by `statements`
+    pub journal: Vec<PruneJournalEntry>,
 }
 
 /// Main entry point: prune expired items from trash.
@@ -100,9 +226,6 @@ pub fn prune_vault(input: PruneInput) -> VaultResult<PruneOutput> {
             format!("Invalid current_time format: {}", input.current_time)
         ))?;
 
-    // Calculate cutoff date
-    let cutoff_date = now - Duration::days(input.retention_days as i64);
-
     // Find Items table
     let items_table = input.tables.iter().find(|t| t.name == "Items");
     if items_table.is_none() {
@@ -110,13 +233,14 @@ pub fn prune_vault(input: PruneInput) -> VaultResult<PruneOutput> {
             success: true,
             statements: vec![],
             stats,
+            journal: vec![],
         });
     }
 
     let items = &items_table.unwrap().records;
 
     // Find items that are in trash (DeletedAt set) and older than retention period
-    let mut expired_item_ids: Vec<String> = Vec::new();
+    let mut expired_items: Vec<ExpiredItem> = Vec::new();
 
     for item in items {
         // Skip if already permanently deleted
@@ -126,6 +250,11 @@ pub fn prune_vault(input: PruneInput) -> VaultResult<PruneOutput> {
             }
         }
 
+        // Skip items pinned to never expire, regardless of any retention override
+        if item.get("NeverExpire").and_then(|v| v.as_bool()).unwrap_or(false) {
+            continue;
+        }
+
         // Check if item is in trash (DeletedAt is set and not null)
         if let Some(deleted_at) = item.get("DeletedAt") {
             if deleted_at.is_null() {
@@ -134,9 +263,15 @@ pub fn prune_vault(input: PruneInput) -> VaultResult<PruneOutput> {
 
             if let Some(deleted_at_str) = deleted_at.as_str() {
                 if let Some(deleted_date) = parse_datetime(deleted_at_str) {
-                    if deleted_date < cutoff_date {
-                        if let Some(id) = item.get("Id").and_then(|v| v.as_str()) {
-                            expired_item_ids.push(id.to_string());
+                    if let Some(id) = item.get("Id").and_then(|v| v.as_str()) {
+                        let retention_days = effective_retention_days(item, id, &input);
+                        let cutoff_date = now - Duration::days(retention_days as i64);
+                        if deleted_date < cutoff_date {
+                            expired_items.push(ExpiredItem {
+                                id: id.to_string(),
+                                deleted_at: deleted_at_str.to_string(),
+                                item_type: item.get("Type").and_then(|v| v.as_str()).map(String::from),
+                            });
                         }
                     }
                 }
@@ -144,41 +279,69 @@ pub fn prune_vault(input: PruneInput) -> VaultResult<PruneOutput> {
         }
     }
 
-    // If no expired items, return early
-    if expired_item_ids.is_empty() {
+    let now_str = now.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+
+    // Sweep child tables for rows whose parent Item is gone (hard-deleted by an
+    // older client, or lost during a partial sync) rather than pruned this run.
+    // These never get revisited by the per-item cascade below, so without this
+    // pass they'd linger forever.
+    let live_item_ids: HashSet<&str> = items.iter()
+        .filter(|it| match it.get("IsDeleted") {
+            Some(v) => v.as_i64() != Some(1) && v.as_bool() != Some(true),
+            None => true,
+        })
+        .filter_map(|it| it.get("Id").and_then(|v| v.as_str()))
+        .collect();
+
+    for table_name in ["FieldValues", "Attachments", "TotpCodes", "Passkeys"] {
+        if let Some(table) = input.tables.iter().find(|t| t.name == table_name) {
+            for orphan_item_id in orphaned_parent_ids(&table.records, "ItemId", &live_item_ids) {
+                let related_count = count_related_records(&table.records, "ItemId", &orphan_item_id);
+                if related_count > 0 {
+                    statements.push(child_delete_statement(table_name, &orphan_item_id, input.hard_delete, &now_str));
+                    stats.orphans_pruned += related_count;
+                }
+            }
+        }
+    }
+
+    // If there's nothing expired and nothing orphaned, return early
+    if expired_items.is_empty() && statements.is_empty() {
         return Ok(PruneOutput {
             success: true,
             statements: vec![],
             stats,
+            journal: vec![],
         });
     }
 
     // Generate SQL statements to permanently delete the expired items and related entities
-    let now_str = now.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+    let mut journal: Vec<PruneJournalEntry> = Vec::new();
+
+    for expired in &expired_items {
+        let item_id = &expired.id;
+
+        let mut entry = PruneJournalEntry {
+            item_id: item_id.clone(),
+            item_type: expired.item_type.clone(),
+            deleted_at: expired.deleted_at.clone(),
+            pruned_at: now_str.clone(),
+            field_values_removed: 0,
+            attachments_removed: 0,
+            totp_codes_removed: 0,
+            passkeys_removed: 0,
+        };
 
-    for item_id in &expired_item_ids {
-        // Mark item as permanently deleted
-        statements.push(SqlStatement {
-            sql: "UPDATE Items SET IsDeleted = 1, UpdatedAt = ? WHERE Id = ?".to_string(),
-            params: vec![
-                serde_json::json!(now_str),
-                serde_json::json!(item_id),
-            ],
-        });
-        stats.items_pruned += 1;
+        // Remove child rows before the parent Item, so a hard-delete run
+        // never violates a foreign-key constraint on ItemId.
 
         // Mark related FieldValues as deleted
         if let Some(field_values_table) = input.tables.iter().find(|t| t.name == "FieldValues") {
             let related_count = count_related_records(&field_values_table.records, "ItemId", item_id);
             if related_count > 0 {
-                statements.push(SqlStatement {
-                    sql: "UPDATE FieldValues SET IsDeleted = 1, UpdatedAt = ? WHERE ItemId = ? AND IsDeleted = 0".to_string(),
-                    params: vec![
-                        serde_json::json!(now_str),
-                        serde_json::json!(item_id),
-                    ],
-                });
+                statements.push(child_delete_statement("FieldValues", item_id, input.hard_delete, &now_str));
                 stats.field_values_pruned += related_count;
+                entry.field_values_removed = related_count;
             }
         }
 
@@ -186,14 +349,9 @@ pub fn prune_vault(input: PruneInput) -> VaultResult<PruneOutput> {
         if let Some(attachments_table) = input.tables.iter().find(|t| t.name == "Attachments") {
             let related_count = count_related_records(&attachments_table.records, "ItemId", item_id);
             if related_count > 0 {
-                statements.push(SqlStatement {
-                    sql: "UPDATE Attachments SET IsDeleted = 1, UpdatedAt = ? WHERE ItemId = ? AND IsDeleted = 0".to_string(),
-                    params: vec![
-                        serde_json::json!(now_str),
-                        serde_json::json!(item_id),
-                    ],
-                });
+                statements.push(child_delete_statement("Attachments", item_id, input.hard_delete, &now_str));
                 stats.attachments_pruned += related_count;
+                entry.attachments_removed = related_count;
             }
         }
 
@@ -201,14 +359,9 @@ pub fn prune_vault(input: PruneInput) -> VaultResult<PruneOutput> {
         if let Some(totp_table) = input.tables.iter().find(|t| t.name == "TotpCodes") {
             let related_count = count_related_records(&totp_table.records, "ItemId", item_id);
             if related_count > 0 {
-                statements.push(SqlStatement {
-                    sql: "UPDATE TotpCodes SET IsDeleted = 1, UpdatedAt = ? WHERE ItemId = ? AND IsDeleted = 0".to_string(),
-                    params: vec![
-                        serde_json::json!(now_str),
-                        serde_json::json!(item_id),
-                    ],
-                });
+                statements.push(child_delete_statement("TotpCodes", item_id, input.hard_delete, &now_str));
                 stats.totp_codes_pruned += related_count;
+                entry.totp_codes_removed = related_count;
             }
         }
 
@@ -216,25 +369,58 @@ pub fn prune_vault(input: PruneInput) -> VaultResult<PruneOutput> {
         if let Some(passkeys_table) = input.tables.iter().find(|t| t.name == "Passkeys") {
             let related_count = count_related_records(&passkeys_table.records, "ItemId", item_id);
             if related_count > 0 {
-                statements.push(SqlStatement {
-                    sql: "UPDATE Passkeys SET IsDeleted = 1, UpdatedAt = ? WHERE ItemId = ? AND IsDeleted = 0".to_string(),
-                    params: vec![
-                        serde_json::json!(now_str),
-                        serde_json::json!(item_id),
-                    ],
-                });
+                statements.push(child_delete_statement("Passkeys", item_id, input.hard_delete, &now_str));
                 stats.passkeys_pruned += related_count;
+                entry.passkeys_removed = related_count;
             }
         }
+
+        // Remove the item itself now that its children are gone
+        statements.push(item_delete_statement(item_id, input.hard_delete, &now_str));
+        stats.items_pruned += 1;
+
+        // Append an audit row so the removal can be reconciled later
+        statements.push(SqlStatement {
+            sql: "INSERT INTO PruneHistory (ItemId, ItemType, DeletedAt, PrunedAt, FieldValuesRemoved, AttachmentsRemoved, TotpCodesRemoved, PasskeysRemoved) VALUES (?, ?, ?, ?, ?, ?, ?, ?)".to_string(),
+            params: vec![
+                serde_json::json!(entry.item_id),
+                serde_json::json!(entry.item_type),
+                serde_json::json!(entry.deleted_at),
+                serde_json::json!(entry.pruned_at),
+                serde_json::json!(entry.field_values_removed),
+                serde_json::json!(entry.attachments_removed),
+                serde_json::json!(entry.totp_codes_removed),
+                serde_json::json!(entry.passkeys_removed),
+            ],
+        });
+
+        journal.push(entry);
+    }
+
+    // Compact the file after real DELETEs; a no-op if nothing above emitted one.
+    if input.hard_delete && !statements.is_empty() {
+        statements.push(SqlStatement {
+            sql: "VACUUM".to_string(),
+            params: vec![],
+        });
     }
 
     Ok(PruneOutput {
         success: true,
         statements,
         stats,
+        journal,
     })
 }
 
+/// An item found in the trash past its retention cutoff, carrying just
+/// enough detail from its `Items` row to produce a [`PruneJournalEntry`].
+struct ExpiredItem {
+    id: String,
+    deleted_at: String,
+    item_type: Option<String>,
+}
+
 /// Prune vault using JSON strings.
 /// Convenience function for FFI.
 pub fn prune_vault_json(input_json: &str) -> VaultResult<String> {
@@ -279,6 +465,31 @@ fn count_related_records(records: &[Record], fk_column: &str, fk_value: &str) ->
     }).count() as u32
 }
 
+/// Returns the distinct `fk_column` values among `records` (excluding
+/// already-deleted rows) that do not name a live parent Id.
+fn orphaned_parent_ids(records: &[Record], fk_column: &str, live_parent_ids: &HashSet<&str>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut orphans = Vec::new();
+
+    for record in records {
+        let not_deleted = match record.get("IsDeleted") {
+            Some(v) => v.as_i64() != Some(1) && v.as_bool() != Some(true),
+            None => true,
+        };
+        if !not_deleted {
+            continue;
+        }
+
+        if let Some(parent_id) = record.get(fk_column).and_then(|v| v.as_str()) {
+            if !live_parent_ids.contains(parent_id) && seen.insert(parent_id.to_string()) {
+                orphans.push(parent_id.to_string());
+            }
+        }
+    }
+
+    orphans
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -325,6 +536,8 @@ mod tests {
             ],
             retention_days: 30,
             current_time: now_str,
+            retention_overrides: None,
+            hard_delete: false,
         };
 
         let output = prune_vault(input).unwrap();
@@ -335,6 +548,44 @@ mod tests {
         assert!(output.statements.len() >= 2); // At least item + field value updates
     }
 
+    #[test]
+    fn test_hard_delete_emits_deletes_then_vacuum() {
+        let now = Utc::now();
+        let now_str = now.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+        let old_date = (now - Duration::days(60)).format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+
+        let input = PruneInput {
+            tables: vec![
+                TableData {
+                    name: "Items".to_string(),
+                    records: vec![make_item_record("item-1", Some(&old_date), false)],
+                },
+                TableData {
+                    name: "FieldValues".to_string(),
+                    records: vec![make_field_value_record("fv-1", "item-1", false)],
+                },
+            ],
+            retention_days: 30,
+            current_time: now_str,
+            retention_overrides: None,
+            hard_delete: true,
+        };
+
+        let output = prune_vault(input).unwrap();
+
+        assert_eq!(output.stats.items_pruned, 1);
+        assert_eq!(output.stats.field_values_pruned, 1);
+        assert_eq!(output.statements.last().unwrap().sql, "VACUUM");
+
+        let field_values_pos = output.statements.iter()
+            .position(|s| s.sql.starts_with("DELETE FROM FieldValues"))
+            .expect("expected a DELETE FROM FieldValues statement");
+        let items_pos = output.statements.iter()
+            .position(|s| s.sql.starts_with("DELETE FROM Items"))
+            .expect("expected a DELETE FROM Items statement");
+        assert!(field_values_pos < items_pos, "child rows must be deleted before their parent Item");
+    }
+
     #[test]
     fn test_no_prune_recent_items() {
         let now = Utc::now();
@@ -351,6 +602,8 @@ mod tests {
             ],
             retention_days: 30,
             current_time: now_str,
+            retention_overrides: None,
+            hard_delete: false,
         };
 
         let output = prune_vault(input).unwrap();
@@ -373,6 +626,8 @@ mod tests {
             ],
             retention_days: 30,
             current_time: now_str,
+            retention_overrides: None,
+            hard_delete: false,
         };
 
         let output = prune_vault(input).unwrap();
@@ -398,6 +653,8 @@ mod tests {
             ],
             retention_days: 30,
             current_time: now_str,
+            retention_overrides: None,
+            hard_delete: false,
         };
 
         let output = prune_vault(input).unwrap();
@@ -407,6 +664,187 @@ mod tests {
         assert!(output.statements.is_empty());
     }
 
+    #[test]
+    fn test_per_item_retention_override_keeps_item() {
+        let now = Utc::now();
+        let now_str = now.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+        // Deleted 10 days ago: past the 7-day per-type default but within
+        // this item's own 90-day override.
+        let deleted_date = (now - Duration::days(10)).format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+
+        let mut item = make_item_record("item-1", Some(&deleted_date), false);
+        item.insert("Type".to_string(), serde_json::json!("Attachment"));
+
+        let mut per_type_days = HashMap::new();
+        per_type_days.insert("Attachment".to_string(), 7);
+        let mut per_item_days = HashMap::new();
+        per_item_days.insert("item-1".to_string(), 90);
+
+        let input = PruneInput {
+            tables: vec![TableData { name: "Items".to_string(), records: vec![item] }],
+            retention_days: 30,
+            current_time: now_str,
+            retention_overrides: Some(RetentionOverrides { per_type_days, per_item_days }),
+            hard_delete: false,
+        };
+
+        let output = prune_vault(input).unwrap();
+
+        assert_eq!(output.stats.items_pruned, 0);
+    }
+
+    #[test]
+    fn test_per_type_retention_override_prunes_sooner() {
+        let now = Utc::now();
+        let now_str = now.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+        // Deleted 10 days ago: within the global 30-day default, but past
+        // the 7-day override for the "Attachment" type.
+        let deleted_date = (now - Duration::days(10)).format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+
+        let mut item = make_item_record("item-1", Some(&deleted_date), false);
+        item.insert("Type".to_string(), serde_json::json!("Attachment"));
+
+        let mut per_type_days = HashMap::new();
+        per_type_days.insert("Attachment".to_string(), 7);
+
+        let input = PruneInput {
+            tables: vec![TableData { name: "Items".to_string(), records: vec![item] }],
+            retention_days: 30,
+            current_time: now_str,
+            retention_overrides: Some(RetentionOverrides { per_type_days, per_item_days: HashMap::new() }),
+            hard_delete: false,
+        };
+
+        let output = prune_vault(input).unwrap();
+
+        assert_eq!(output.stats.items_pruned, 1);
+    }
+
+    #[test]
+    fn test_never_expire_pin_blocks_prune() {
+        let now = Utc::now();
+        let now_str = now.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+        let old_date = (now - Duration::days(365)).format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+
+        let mut item = make_item_record("item-1", Some(&old_date), false);
+        item.insert("NeverExpire".to_string(), serde_json::json!(true));
+
+        let input = PruneInput {
+            tables: vec![TableData { name: "Items".to_string(), records: vec![item] }],
+            retention_days: 30,
+            current_time: now_str,
+            retention_overrides: None,
+            hard_delete: false,
+        };
+
+        let output = prune_vault(input).unwrap();
+
+        assert_eq!(output.stats.items_pruned, 0);
+    }
+
+    #[test]
+    fn test_orphan_sweep_prunes_dangling_children() {
+        let now_str = Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+
+        let input = PruneInput {
+            tables: vec![
+                TableData {
+                    name: "Items".to_string(),
+                    records: vec![make_item_record("item-1", None, false)],
+                },
+                TableData {
+                    name: "FieldValues".to_string(),
+                    records: vec![
+                        make_field_value_record("fv-1", "item-1", false),
+                        make_field_value_record("fv-2", "item-missing", false),
+                    ],
+                },
+            ],
+            retention_days: 30,
+            current_time: now_str,
+            retention_overrides: None,
+            hard_delete: false,
+        };
+
+        let output = prune_vault(input).unwrap();
+
+        assert_eq!(output.stats.orphans_pruned, 1);
+        assert_eq!(output.stats.field_values_pruned, 0);
+        let has_orphan_update = output.statements.iter().any(|s| {
+            s.sql.starts_with("UPDATE FieldValues")
+                && s.params.iter().any(|p| p == &serde_json::json!("item-missing"))
+        });
+        assert!(has_orphan_update);
+    }
+
+    #[test]
+    fn test_no_orphan_sweep_when_parent_still_in_trash() {
+        let now_str = Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+        let recent_date = (Utc::now() - Duration::days(5)).format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+
+        let input = PruneInput {
+            tables: vec![
+                TableData {
+                    name: "Items".to_string(),
+                    records: vec![make_item_record("item-1", Some(&recent_date), false)],
+                },
+                TableData {
+                    name: "FieldValues".to_string(),
+                    records: vec![make_field_value_record("fv-1", "item-1", false)],
+                },
+            ],
+            retention_days: 30,
+            current_time: now_str,
+            retention_overrides: None,
+            hard_delete: false,
+        };
+
+        let output = prune_vault(input).unwrap();
+
+        assert_eq!(output.stats.orphans_pruned, 0);
+        assert!(output.statements.is_empty());
+    }
+
+    #[test]
+    fn test_prune_journal_entry() {
+        let now = Utc::now();
+        let now_str = now.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+        let old_date = (now - Duration::days(60)).format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+
+        let mut item = make_item_record("item-1", Some(&old_date), false);
+        item.insert("Type".to_string(), serde_json::json!("Login"));
+
+        let input = PruneInput {
+            tables: vec![
+                TableData {
+                    name: "Items".to_string(),
+                    records: vec![item],
+                },
+                TableData {
+                    name: "FieldValues".to_string(),
+                    records: vec![make_field_value_record("fv-1", "item-1", false)],
+                },
+            ],
+            retention_days: 30,
+            current_time: now_str,
+            retention_overrides: None,
+            hard_delete: false,
+        };
+
+        let output = prune_vault(input).unwrap();
+
+        assert_eq!(output.journal.len(), 1);
+        let entry = &output.journal[0];
+        assert_eq!(entry.item_id, "item-1");
+        assert_eq!(entry.item_type.as_deref(), Some("Login"));
+        assert_eq!(entry.deleted_at, old_date);
+        assert_eq!(entry.field_values_removed, 1);
+
+        let has_prune_history_insert = output.statements.iter()
+            .any(|s| s.sql.starts_with("INSERT INTO PruneHistory"));
+        assert!(has_prune_history_insert);
+    }
+
     #[test]
     fn test_prune_json_api() {
         let now = Utc::now();
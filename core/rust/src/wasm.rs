@@ -1,4 +1,8 @@
 //! WASM bindings for browser extension.
+//!
+//! Mirrors the C FFI layer (`ffi.rs`): merge, prune, and credential filtering
+//! are all exposed here too, so the browser extension gets the same behavior
+//! as the native/.NET clients without round-tripping through native code.
 
 use wasm_bindgen::prelude::*;
 